@@ -1,43 +1,166 @@
 use std::collections::HashMap;
+use crate::competition::{Competition, Scope, Season};
+use crate::date::DateRange;
+use crate::discipline::{self, DisciplineRecord, Suspension, SuspensionRules};
+use crate::elo::{EloConfig, EloRatings};
+use crate::head_to_head::HeadToHead;
+use crate::league_table::{LeagueTable, TableRules};
+use crate::lineup::{self, PlayerAppearances};
 use crate::match_data::MatchData;
+use crate::fixtures::Fixture;
+use crate::form::{self, StreakReport};
+use crate::predictor::{PoissonConfig, PoissonModel};
+use crate::ratings::{PlayerRatings, RatingConfig};
+use crate::record::Record;
+use crate::simulator::{self, OutcomeModel, SeasonForecast, SimulationConfig};
+use crate::splits::{self, HomeAdvantage, HomeAwaySplit, Venue};
+use crate::team::Team;
+use crate::xg::{PlayerXg, TeamXg, XgModel};
 
 pub struct FootballAnalytics {
     matches: Vec<MatchData>,
+    competitions: Vec<Competition>,
+}
+
+impl Default for FootballAnalytics {
+    fn default() -> Self {
+        Self::new()
+    }
 }
 
 impl FootballAnalytics {
     pub fn new() -> Self {
         FootballAnalytics {
             matches: Vec::new(),
+            competitions: Vec::new(),
+        }
+    }
+
+    // Registering a competition is optional; matches can be tagged with any
+    // name. Re-adding a name replaces the earlier entry.
+    pub fn add_competition(&mut self, competition: Competition) {
+        match self.competitions.iter_mut().find(|c| c.name == competition.name) {
+            Some(existing) => *existing = competition,
+            None => self.competitions.push(competition),
+        }
+    }
+
+    pub fn competitions(&self) -> &[Competition] {
+        &self.competitions
+    }
+
+    pub fn competition(&self, name: &str) -> Option<&Competition> {
+        self.competitions.iter().find(|c| c.name == name)
+    }
+
+    // Seasons with at least one match in the given competition (or in any,
+    // for None), oldest first
+    pub fn seasons(&self, competition: Option<&str>) -> Vec<Season> {
+        let scope = Scope {
+            competition: competition.map(str::to_string),
+            season: None,
+        };
+        let mut seasons: Vec<Season> = self.matches_for(&scope).iter().filter_map(|m| m.season()).collect();
+        seasons.sort();
+        seasons.dedup();
+        seasons
+    }
+
+    pub fn matches_for(&self, scope: &Scope) -> Vec<&MatchData> {
+        self.matches.iter().filter(|m| scope.contains(m)).collect()
+    }
+
+    // A copy holding only the matches in `scope`, so every query can be run
+    // for one competition or season. The unscoped instance aggregates them all.
+    pub fn scoped(&self, scope: &Scope) -> FootballAnalytics {
+        FootballAnalytics {
+            matches: self.matches_for(scope).into_iter().cloned().collect(),
+            competitions: self.competitions.clone(),
         }
     }
 
+    // Keeps matches sorted by date and kickoff; matches on the same day
+    // without a kickoff time stay in insertion order
     pub fn add_match(&mut self, match_data: MatchData) {
-        self.matches.push(match_data);
+        let key = match_data.kickoff_order();
+        let index = self.matches.partition_point(|m| m.kickoff_order() <= key);
+        self.matches.insert(index, match_data);
+    }
+
+    // All matches, oldest first
+    pub fn matches(&self) -> &[MatchData] {
+        &self.matches
+    }
+
+    pub fn matches_in(&self, range: &DateRange) -> Vec<&MatchData> {
+        self.matches.iter().filter(|m| range.contains(m.date)).collect()
+    }
+
+    // A team's most recent `n` matches, oldest first
+    pub fn last_matches(&self, team: &str, n: usize) -> Vec<&MatchData> {
+        let mut recent: Vec<&MatchData> = self.matches.iter().rev().filter(|m| m.involves(team)).take(n).collect();
+        recent.reverse();
+        recent
+    }
+
+    // Last `n` results, oldest first, e.g. "WWDLW"
+    pub fn form(&self, team: &str, n: usize) -> String {
+        form::form_string(team, self.last_matches(team, n))
+    }
+
+    pub fn streaks(&self, team: &str) -> StreakReport {
+        self.streaks_in(team, &DateRange::all())
+    }
+
+    pub fn streaks_in(&self, team: &str, range: &DateRange) -> StreakReport {
+        form::streaks(team, self.matches_in(range))
     }
 
     pub fn get_top_scorers(&self, top_n: usize) -> Vec<(String, usize)> {
+        self.get_top_scorers_in(top_n, &DateRange::all())
+    }
+
+    pub fn get_top_scorers_in(&self, top_n: usize, range: &DateRange) -> Vec<(String, usize)> {
         let mut scorer_counts: HashMap<String, usize> = HashMap::new();
 
-        for m in &self.matches {
-            for scorer in &m.home_scorers {
-                *scorer_counts.entry(scorer.clone()).or_insert(0) += 1;
-            }
-            for scorer in &m.away_scorers {
-                *scorer_counts.entry(scorer.clone()).or_insert(0) += 1;
+        for m in self.matches_in(range) {
+            for scorer in m.get_all_scorers() {
+                *scorer_counts.entry(scorer).or_insert(0) += 1;
             }
         }
 
         let mut scorer_vec: Vec<(String, usize)> = scorer_counts.into_iter().collect();
-        scorer_vec.sort_by(|a, b| b.1.cmp(&a.1));
+        scorer_vec.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
         scorer_vec.into_iter().take(top_n).collect()
     }
 
+    pub fn get_top_assisters(&self, top_n: usize) -> Vec<(String, usize)> {
+        self.get_top_assisters_in(top_n, &DateRange::all())
+    }
+
+    pub fn get_top_assisters_in(&self, top_n: usize, range: &DateRange) -> Vec<(String, usize)> {
+        let mut assist_counts: HashMap<String, usize> = HashMap::new();
+
+        for m in self.matches_in(range) {
+            for assister in m.get_all_assists() {
+                *assist_counts.entry(assister).or_insert(0) += 1;
+            }
+        }
+
+        let mut assist_vec: Vec<(String, usize)> = assist_counts.into_iter().collect();
+        assist_vec.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+        assist_vec.into_iter().take(top_n).collect()
+    }
+
     pub fn get_team_stats(&self) -> HashMap<String, (usize, usize, usize)> {
+        self.get_team_stats_in(&DateRange::all())
+    }
+
+    pub fn get_team_stats_in(&self, range: &DateRange) -> HashMap<String, (usize, usize, usize)> {
         // (games_played, goals_for, goals_against)
         let mut stats: HashMap<String, (usize, usize, usize)> = HashMap::new();
 
-        for m in &self.matches {
+        for m in self.matches_in(range) {
             let home = stats.entry(m.home_team.clone()).or_insert((0, 0, 0));
             home.0 += 1;
             home.1 += m.home_score as usize;
@@ -52,54 +175,147 @@ impl FootballAnalytics {
         stats
     }
 
-    pub fn head_to_head(&self, team1: &str, team2: &str) -> Option<(usize, usize, usize)> {
-        let mut team1_wins = 0;
-        let mut team2_wins = 0;
-        let mut draws = 0;
-
-        for m in &self.matches {
-            if (m.home_team == team1 && m.away_team == team2) || (m.home_team == team2 && m.away_team == team1) {
-                if m.home_score > m.away_score {
-                    if m.home_team == team1 {
-                        team1_wins += 1;
-                    } else {
-                        team2_wins += 1;
-                    }
-                } else if m.home_score < m.away_score {
-                    if m.away_team == team1 {
-                        team1_wins += 1;
-                    } else {
-                        team2_wins += 1;
-                    }
-                } else {
-                    draws += 1;
-                }
-            }
-        }
+    pub fn home_away_splits(&self) -> Vec<HomeAwaySplit> {
+        self.home_away_splits_in(&DateRange::all())
+    }
 
-        Some((team1_wins, team2_wins, draws))
+    pub fn home_away_splits_in(&self, range: &DateRange) -> Vec<HomeAwaySplit> {
+        splits::home_away_splits(self.matches_in(range))
+    }
+
+    pub fn home_table(&self) -> Vec<(String, Record)> {
+        splits::venue_table(&self.home_away_splits(), Venue::Home)
+    }
+
+    pub fn away_table(&self) -> Vec<(String, Record)> {
+        splits::venue_table(&self.home_away_splits(), Venue::Away)
+    }
+
+    pub fn home_advantage(&self) -> HomeAdvantage {
+        self.home_advantage_in(&DateRange::all())
+    }
+
+    pub fn home_advantage_in(&self, range: &DateRange) -> HomeAdvantage {
+        splits::home_advantage(self.matches_in(range))
     }
-}
 
+    pub fn elo_ratings(&self, config: EloConfig) -> EloRatings {
+        EloRatings::from_matches(&self.matches, config)
+    }
 
+    // Player ratings replayed over every match, seeded from the squads
+    pub fn player_ratings(&self, teams: &[Team], config: RatingConfig) -> PlayerRatings {
+        PlayerRatings::from_matches(teams, &self.matches, config)
+    }
 
+    pub fn poisson_model(&self, config: PoissonConfig) -> PoissonModel {
+        PoissonModel::fit(&self.matches, config)
+    }
 
-⚽ Football Analytics System
-============================
+    pub fn team_xg(&self, model: &XgModel) -> HashMap<String, TeamXg> {
+        model.team_totals(&self.matches)
+    }
 
-📊 Team Analysis:
-Barcelona - Average Rating: 88.2
-Real Madrid - Average Rating: 88.5
+    pub fn player_xg(&self, model: &XgModel) -> HashMap<String, PlayerXg> {
+        model.player_totals(&self.matches)
+    }
 
-🏆 Top Scorers:
-1. Messi - 4 goals
-2. Busquets - 3 goals
-3. Pique - 2 goals
+    // Appearances, starts and minutes from recorded lineups, keyed by player
+    pub fn appearances(&self) -> HashMap<String, PlayerAppearances> {
+        self.appearances_in(&DateRange::all())
+    }
 
-📈 Team Performance:
-Real Madrid: 2 games, 3 goals for, 5 goals against
-Atletico Madrid: 1 games, 0 goals for, 4 goals against
-FC Barcelona: 3 games, 9 goals for, 3 goals against
+    pub fn appearances_in(&self, range: &DateRange) -> HashMap<String, PlayerAppearances> {
+        lineup::appearances(self.matches_in(range))
+    }
+
+    pub fn player_discipline(&self) -> HashMap<String, DisciplineRecord> {
+        self.player_discipline_in(&DateRange::all())
+    }
+
+    pub fn player_discipline_in(&self, range: &DateRange) -> HashMap<String, DisciplineRecord> {
+        discipline::player_discipline(self.matches_in(range))
+    }
 
-🎯 Head-to-Head Analysis:
-Barcelona vs Real Madrid: 2-0-0 (W-L-D)
\ No newline at end of file
+    pub fn team_discipline(&self) -> HashMap<String, DisciplineRecord> {
+        self.team_discipline_in(&DateRange::all())
+    }
+
+    pub fn team_discipline_in(&self, range: &DateRange) -> HashMap<String, DisciplineRecord> {
+        discipline::team_discipline(self.matches_in(range))
+    }
+
+    // Bans still outstanding after every match played so far
+    pub fn suspensions(&self, rules: &SuspensionRules) -> Vec<Suspension> {
+        rules.suspensions(&self.matches)
+    }
+
+    pub fn unavailable_for(&self, fixture: &Fixture, rules: &SuspensionRules) -> Vec<Suspension> {
+        rules.unavailable_for(fixture, &self.matches)
+    }
+
+    // Plays out `remaining` many times on top of the results so far
+    pub fn simulate_season<M: OutcomeModel>(
+        &self,
+        remaining: &[Fixture],
+        model: &M,
+        config: &SimulationConfig,
+    ) -> SeasonForecast {
+        simulator::simulate_season(&self.matches, remaining, model, config)
+    }
+
+    pub fn league_table(&self, rules: &TableRules) -> LeagueTable {
+        LeagueTable::from_matches(&self.matches, rules)
+    }
+
+    // None if the teams have never met
+    pub fn head_to_head(&self, team1: &str, team2: &str) -> Option<HeadToHead> {
+        self.head_to_head_in(team1, team2, &DateRange::all())
+    }
+
+    pub fn head_to_head_in(&self, team1: &str, team2: &str, range: &DateRange) -> Option<HeadToHead> {
+        HeadToHead::from_matches(team1, team2, self.matches_in(range))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::events::{Goal, GoalKind};
+    use crate::test_util::date;
+
+    #[test]
+    fn test_top_assisters() {
+        let mut analytics = FootballAnalytics::new();
+        let goal = |scorer: &str, assist: &str| Goal::new(scorer.to_string(), 10, GoalKind::OpenPlay).with_assist(assist.to_string());
+        analytics.add_match(MatchData::new(
+            date(2023, 9, 2),
+            "A".to_string(),
+            "B".to_string(),
+            2,
+            1,
+            vec![goal("Lewandowski", "Pedri"), goal("Pedri", "Gavi")],
+            vec![goal("Griezmann", "Koke")],
+        ));
+        analytics.add_match(MatchData::new(
+            date(2024, 2, 3),
+            "B".to_string(),
+            "A".to_string(),
+            0,
+            2,
+            vec![],
+            vec![
+                goal("Lewandowski", "Pedri"),
+                Goal::new("Gimenez".to_string(), 80, GoalKind::OwnGoal),
+            ],
+        ));
+
+        // Ties are broken by name; goals without an assist don't count
+        assert_eq!(
+            analytics.get_top_assisters(3),
+            vec![("Pedri".to_string(), 2), ("Gavi".to_string(), 1), ("Koke".to_string(), 1)]
+        );
+        let since_2024 = DateRange::since(date(2024, 1, 1));
+        assert_eq!(analytics.get_top_assisters_in(5, &since_2024), vec![("Pedri".to_string(), 1)]);
+    }
+}
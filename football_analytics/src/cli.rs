@@ -0,0 +1,599 @@
+use std::fmt;
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+
+use serde_json::{json, Map, Value};
+
+use crate::analytics::FootballAnalytics;
+use crate::competition::{Scope, Season};
+use crate::importer::{self, ImportError, OnBadRow};
+use crate::league_table::TableRules;
+use crate::persistence::{Dataset, PersistenceError};
+use crate::registry::{NameMatch, PlayerRegistry};
+use crate::server::Server;
+use crate::team::Team;
+
+// Command-line front end. Every command reads the JSON dataset written by
+// `persistence` (football.json unless --data says otherwise); `import`
+// adds CSV results to it, creating the file if needed.
+
+pub const USAGE: &str = "\
+usage: football_analytics [--data FILE] [--format plain|json|csv]
+                          [--competition NAME] [--season 2023/24] [COMMAND]
+       football_analytics --help
+
+commands:
+  import CSV [--skip-bad-rows]   add results from a football-data.co.uk style CSV
+  table                          league table
+  scorers [--top N]              top scorers (default 10)
+  h2h TEAM1 TEAM2                head-to-head record
+  team TEAM                      squad and record
+  player NAME                    goals, assists and minutes
+  serve [--port N]               JSON API on localhost (default port 8080)
+  demo                           run the built-in demo (the default)
+
+--competition and --season tag the rows added by import, and limit table,
+scorers, h2h, team and player to one competition or season; without them
+results cover every competition.";
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Format {
+    Plain,
+    Json,
+    Csv,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Command {
+    Import { csv: PathBuf, on_bad_row: OnBadRow },
+    Table,
+    Scorers { top: usize },
+    HeadToHead { team1: String, team2: String },
+    Team { name: String },
+    Player { name: String },
+    Serve { port: u16 },
+    Demo,
+    Help,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Options {
+    pub data: PathBuf,
+    pub format: Format,
+    pub scope: Scope,
+    pub command: Command,
+}
+
+#[derive(Debug)]
+pub enum CliError {
+    Usage(String),
+    NotFound(String),
+    Import(ImportError),
+    Persistence(PersistenceError),
+    Io(io::Error),
+}
+
+// Rows of named columns; each format renders the same data
+struct Output {
+    title: Option<String>,      // Only shown in plain output
+    columns: Vec<&'static str>,
+    rows: Vec<Vec<Value>>,
+}
+
+impl Format {
+    pub fn parse(s: &str) -> Option<Format> {
+        match s.to_ascii_lowercase().as_str() {
+            "plain" => Some(Format::Plain),
+            "json" => Some(Format::Json),
+            "csv" => Some(Format::Csv),
+            _ => None,
+        }
+    }
+}
+
+pub fn parse_args(args: &[String]) -> Result<Options, CliError> {
+    let mut data = PathBuf::from("football.json");
+    let mut format = Format::Plain;
+    let mut top = 10;
+    let mut port = 8080;
+    let mut on_bad_row = OnBadRow::Fail;
+    let mut scope = Scope::all();
+    let mut positional: Vec<&str> = Vec::new();
+
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        let mut value = |flag: &str| {
+            args.next()
+                .ok_or_else(|| CliError::Usage(format!("{} needs a value", flag)))
+        };
+        match arg.as_str() {
+            "--data" => data = PathBuf::from(value("--data")?),
+            "--format" => {
+                let v = value("--format")?;
+                format = Format::parse(v).ok_or_else(|| CliError::Usage(format!("unknown format '{}'", v)))?;
+            }
+            "--top" => {
+                let v = value("--top")?;
+                top = v.parse().map_err(|_| CliError::Usage(format!("--top expects a number, got '{}'", v)))?;
+            }
+            "--port" => {
+                let v = value("--port")?;
+                port = v.parse().map_err(|_| CliError::Usage(format!("--port expects a port number, got '{}'", v)))?;
+            }
+            "--competition" => scope.competition = Some(value("--competition")?.clone()),
+            "--season" => {
+                let v = value("--season")?;
+                scope.season = Some(
+                    Season::parse(v).ok_or_else(|| CliError::Usage(format!("--season expects e.g. 2023/24, got '{}'", v)))?,
+                );
+            }
+            "--skip-bad-rows" => on_bad_row = OnBadRow::Skip,
+            "--help" | "-h" => {
+                return Ok(Options { data, format, scope, command: Command::Help });
+            }
+            flag if flag.starts_with("--") => return Err(CliError::Usage(format!("unknown option '{}'", flag))),
+            _ => positional.push(arg),
+        }
+    }
+
+    let command = match positional.as_slice() {
+        ["import", csv] => Command::Import { csv: PathBuf::from(csv), on_bad_row },
+        ["table"] => Command::Table,
+        ["scorers"] => Command::Scorers { top },
+        ["h2h", team1, team2] => Command::HeadToHead {
+            team1: team1.to_string(),
+            team2: team2.to_string(),
+        },
+        ["team", name] => Command::Team { name: name.to_string() },
+        ["player", name] => Command::Player { name: name.to_string() },
+        ["serve"] => Command::Serve { port },
+        ["demo"] | [] => Command::Demo,
+        [command, ..] => return Err(CliError::Usage(format!("bad arguments for '{}'", command))),
+    };
+
+    Ok(Options { data, format, scope, command })
+}
+
+// Runs everything but `demo`, which lives in the binary
+pub fn run<W: Write>(options: &Options, out: &mut W) -> Result<(), CliError> {
+    let output = match &options.command {
+        Command::Import { csv, on_bad_row } => import(&options.data, csv, *on_bad_row, &options.scope)?,
+        Command::Table => table(&load(options)?.1),
+        Command::Scorers { top } => scorers(&load(options)?.1, *top),
+        Command::HeadToHead { team1, team2 } => head_to_head(&load(options)?.1, team1, team2)?,
+        Command::Team { name } => {
+            let (teams, analytics) = load(options)?;
+            team(&teams, &analytics, name)?
+        }
+        Command::Player { name } => {
+            let (teams, analytics) = load(options)?;
+            player(&teams, &analytics, name)?
+        }
+        Command::Serve { port } => {
+            let mut server = Server::from_dataset(options.data.clone())?;
+            writeln!(out, "Serving {} on http://127.0.0.1:{}", options.data.display(), port)?;
+            return Ok(server.serve(*port)?);
+        }
+        Command::Help => {
+            writeln!(out, "{}", USAGE)?;
+            return Ok(());
+        }
+        Command::Demo => return Err(CliError::Usage("demo is only available from the binary".to_string())),
+    };
+
+    output.write(options.format, out)?;
+    Ok(())
+}
+
+// The dataset's teams, and its matches limited to the requested scope
+fn load(options: &Options) -> Result<(Vec<Team>, FootballAnalytics), CliError> {
+    let (teams, analytics) = Dataset::load(&options.data)?.into_analytics();
+    Ok((teams, analytics.scoped(&options.scope)))
+}
+
+fn import(data: &Path, csv: &Path, on_bad_row: OnBadRow, tag: &Scope) -> Result<Output, CliError> {
+    let (teams, mut analytics) = if data.exists() {
+        Dataset::load(data)?.into_analytics()
+    } else {
+        (Vec::new(), FootballAnalytics::new())
+    };
+
+    let report = importer::import_csv_file(csv, &mut analytics, on_bad_row, tag)?;
+    Dataset::from_analytics(&teams, &analytics).save(data)?;
+
+    let mut rows = vec![vec![json!("imported"), json!(report.imported), json!("")]];
+    for skipped in &report.skipped {
+        rows.push(vec![json!("skipped"), json!(skipped.line), json!(skipped.message)]);
+    }
+    Ok(Output {
+        title: Some(format!("{} now holds {} matches", data.display(), analytics.matches().len())),
+        columns: vec!["status", "count_or_line", "message"],
+        rows,
+    })
+}
+
+fn table(analytics: &FootballAnalytics) -> Output {
+    let table = analytics.league_table(&TableRules::default());
+    Output {
+        title: None,
+        columns: vec!["pos", "team", "p", "w", "d", "l", "gf", "ga", "gd", "pts"],
+        rows: table
+            .rows()
+            .iter()
+            .map(|r| {
+                vec![
+                    json!(r.position),
+                    json!(r.team),
+                    json!(r.record.played),
+                    json!(r.record.won),
+                    json!(r.record.drawn),
+                    json!(r.record.lost),
+                    json!(r.record.goals_for),
+                    json!(r.record.goals_against),
+                    json!(r.goal_difference()),
+                    json!(r.points),
+                ]
+            })
+            .collect(),
+    }
+}
+
+fn scorers(analytics: &FootballAnalytics, top: usize) -> Output {
+    Output {
+        title: None,
+        columns: vec!["rank", "player", "goals"],
+        rows: analytics
+            .get_top_scorers(top)
+            .into_iter()
+            .enumerate()
+            .map(|(i, (player, goals))| vec![json!(i + 1), json!(player), json!(goals)])
+            .collect(),
+    }
+}
+
+fn head_to_head(analytics: &FootballAnalytics, team1: &str, team2: &str) -> Result<Output, CliError> {
+    let h2h = analytics
+        .head_to_head(team1, team2)
+        .ok_or_else(|| CliError::NotFound(format!("{} and {} have never met", team1, team2)))?;
+    Ok(Output {
+        title: None,
+        columns: vec!["team1", "team2", "played", "team1_wins", "draws", "team2_wins", "team1_goals", "team2_goals", "streak"],
+        rows: vec![vec![
+            json!(h2h.team1),
+            json!(h2h.team2),
+            json!(h2h.overall.played),
+            json!(h2h.team1_wins()),
+            json!(h2h.draws()),
+            json!(h2h.team2_wins()),
+            json!(h2h.overall.goals_for),
+            json!(h2h.overall.goals_against),
+            json!(h2h.streak_summary()),
+        ]],
+    })
+}
+
+fn team(teams: &[Team], analytics: &FootballAnalytics, name: &str) -> Result<Output, CliError> {
+    let squad = teams.iter().find(|t| t.name.eq_ignore_ascii_case(name));
+    let team_name = squad.map_or(name, |t| t.name.as_str());
+    let record = analytics.home_away_splits().into_iter().find(|s| s.team == team_name).map(|s| s.overall());
+    if squad.is_none() && record.is_none() {
+        return Err(CliError::NotFound(format!("no team called '{}'", name)));
+    }
+
+    let r = record.unwrap_or_default();
+    let summary = format!("P{} W{} D{} L{} GF{} GA{}", r.played, r.won, r.drawn, r.lost, r.goals_for, r.goals_against);
+    let title = match squad {
+        Some(t) => format!("{} ({}) - {}, average rating {:.1}", t.name, t.city, summary, t.average_rating()),
+        None => format!("{} - {}, no squad on file", name, summary),
+    };
+
+    let mut players: Vec<_> = squad.map(|t| t.players().iter().collect()).unwrap_or_default();
+    players.sort_by_key(|p| p.jersey_number);
+    Ok(Output {
+        title: Some(title),
+        columns: vec!["number", "name", "position", "rating"],
+        rows: players
+            .into_iter()
+            .map(|p| vec![json!(p.jersey_number), json!(p.name), json!(p.position.to_string()), json!(p.rating)])
+            .collect(),
+    })
+}
+
+// Names are looked up through the player registry, so "Messi" finds
+// "Lionel Messi"; without a squad on file the name is matched as written
+fn player(teams: &[Team], analytics: &FootballAnalytics, name: &str) -> Result<Output, CliError> {
+    let mut registry = PlayerRegistry::new();
+    for t in teams {
+        registry.register_team(t);
+    }
+    let appearances = analytics.appearances();
+
+    let (full_name, team, names, goals, assists) = match registry.resolve(name, None) {
+        NameMatch::Resolved(id) => {
+            let registered = registry.get(id).expect("resolved ids are registered");
+            let report = registry.attribute_goals(analytics.matches());
+            let goals = report.attributed.iter().filter(|a| a.scorer == id && !a.goal.is_own_goal()).count();
+            let assists = report.attributed.iter().filter(|a| a.assist == Some(id)).count();
+            (registered.player.name.clone(), registered.team.clone(), registered.aliases.clone(), goals, assists)
+        }
+        NameMatch::Ambiguous(ids) => {
+            let candidates: Vec<String> = ids
+                .iter()
+                .filter_map(|id| registry.get(*id))
+                .map(|p| format!("{} ({})", p.player.name, p.team))
+                .collect();
+            return Err(CliError::NotFound(format!("'{}' could be {}", name, candidates.join(", "))));
+        }
+        NameMatch::Unresolved => {
+            let goals = analytics.matches().iter().flat_map(|m| m.get_all_scorers()).filter(|s| s == name).count();
+            let assists = analytics.matches().iter().flat_map(|m| m.get_all_assists()).filter(|a| a == name).count();
+            if goals == 0 && assists == 0 && !appearances.contains_key(name) {
+                return Err(CliError::NotFound(format!("no player called '{}'", name)));
+            }
+            (name.to_string(), String::new(), vec![name.to_string()], goals, assists)
+        }
+    };
+
+    // Lineups may use any of the player's aliases
+    let apps = names
+        .iter()
+        .find_map(|alias| appearances.iter().find(|(n, _)| n.eq_ignore_ascii_case(alias)).map(|(_, a)| a.clone()))
+        .unwrap_or_default();
+
+    Ok(Output {
+        title: None,
+        columns: vec!["player", "team", "goals", "assists", "appearances", "starts", "minutes"],
+        rows: vec![vec![
+            json!(full_name),
+            json!(team),
+            json!(goals),
+            json!(assists),
+            json!(apps.appearances),
+            json!(apps.starts),
+            json!(apps.minutes),
+        ]],
+    })
+}
+
+impl Output {
+    fn write<W: Write>(&self, format: Format, out: &mut W) -> io::Result<()> {
+        match format {
+            Format::Plain => self.write_plain(out),
+            Format::Json => self.write_json(out),
+            Format::Csv => self.write_csv(out),
+        }
+    }
+
+    fn write_plain<W: Write>(&self, out: &mut W) -> io::Result<()> {
+        if let Some(title) = &self.title {
+            writeln!(out, "{}", title)?;
+        }
+        let cells: Vec<Vec<String>> = self.rows.iter().map(|row| row.iter().map(cell).collect()).collect();
+        let widths: Vec<usize> = self
+            .columns
+            .iter()
+            .enumerate()
+            .map(|(i, c)| cells.iter().map(|row| row[i].chars().count()).chain([c.len()]).max().unwrap_or(0))
+            .collect();
+
+        let line = |values: Vec<&str>| {
+            values
+                .iter()
+                .zip(&widths)
+                .map(|(v, w)| format!("{:<width$}", v, width = w))
+                .collect::<Vec<_>>()
+                .join("  ")
+                .trim_end()
+                .to_string()
+        };
+        writeln!(out, "{}", line(self.columns.clone()))?;
+        for row in &cells {
+            writeln!(out, "{}", line(row.iter().map(String::as_str).collect()))?;
+        }
+        Ok(())
+    }
+
+    fn write_json<W: Write>(&self, out: &mut W) -> io::Result<()> {
+        let objects: Vec<Value> = self
+            .rows
+            .iter()
+            .map(|row| {
+                let object: Map<String, Value> =
+                    self.columns.iter().map(|c| c.to_string()).zip(row.iter().cloned()).collect();
+                Value::Object(object)
+            })
+            .collect();
+        writeln!(out, "{}", serde_json::to_string_pretty(&objects)?)
+    }
+
+    fn write_csv<W: Write>(&self, out: &mut W) -> io::Result<()> {
+        writeln!(out, "{}", self.columns.join(","))?;
+        for row in &self.rows {
+            let fields: Vec<String> = row.iter().map(|v| csv_field(&cell(v))).collect();
+            writeln!(out, "{}", fields.join(","))?;
+        }
+        Ok(())
+    }
+}
+
+fn cell(value: &Value) -> String {
+    match value {
+        Value::String(s) => s.clone(),
+        other => other.to_string(),
+    }
+}
+
+fn csv_field(field: &str) -> String {
+    if field.contains([',', '"', '\n']) {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field.to_string()
+    }
+}
+
+impl fmt::Display for CliError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CliError::Usage(message) => write!(f, "{}\n\n{}", message, USAGE),
+            CliError::NotFound(message) => write!(f, "{}", message),
+            CliError::Import(e) => write!(f, "{}", e),
+            CliError::Persistence(e) => write!(f, "{}", e),
+            CliError::Io(e) => write!(f, "{}", e),
+        }
+    }
+}
+
+impl std::error::Error for CliError {}
+
+impl From<ImportError> for CliError {
+    fn from(e: ImportError) -> Self {
+        CliError::Import(e)
+    }
+}
+
+impl From<PersistenceError> for CliError {
+    fn from(e: PersistenceError) -> Self {
+        CliError::Persistence(e)
+    }
+}
+
+impl From<io::Error> for CliError {
+    fn from(e: io::Error) -> Self {
+        CliError::Io(e)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::events::{Goal, GoalKind};
+    use crate::match_data::MatchData;
+    use crate::player::Position;
+    use crate::test_util::{self, date};
+
+    fn args(s: &str) -> Vec<String> {
+        s.split_whitespace().map(String::from).collect()
+    }
+
+    #[test]
+    fn test_parse_args() {
+        let options = parse_args(&args("--format csv scorers --top 3 --data season.json")).unwrap();
+        assert_eq!(options.format, Format::Csv);
+        assert_eq!(options.data, PathBuf::from("season.json"));
+        assert_eq!(options.command, Command::Scorers { top: 3 });
+        assert_eq!(options.scope, Scope::all());
+
+        let options = parse_args(&args("table --competition Eredivisie --season 2023/24")).unwrap();
+        assert_eq!(options.scope, Scope::competition("Eredivisie").with_season(Season::new(2023).unwrap()));
+        assert!(matches!(parse_args(&args("table --season last")), Err(CliError::Usage(_))));
+        assert!(matches!(parse_args(&args("table --season 65535/00")), Err(CliError::Usage(_))));
+
+        assert!(matches!(
+            parse_args(&args("import results.csv --skip-bad-rows")).unwrap().command,
+            Command::Import { on_bad_row: OnBadRow::Skip, .. }
+        ));
+        assert!(matches!(parse_args(&args("h2h Barcelona")), Err(CliError::Usage(_))));
+        assert!(matches!(parse_args(&args("--format xml table")), Err(CliError::Usage(_))));
+
+        assert_eq!(parse_args(&[]).unwrap().command, Command::Demo);
+        assert_eq!(parse_args(&args("--help")).unwrap().command, Command::Help);
+        assert_eq!(parse_args(&args("table -h --format xml")).unwrap().command, Command::Help);
+
+        let mut out = Vec::new();
+        run(&parse_args(&args("-h")).unwrap(), &mut out).unwrap();
+        assert!(String::from_utf8(out).unwrap().starts_with("usage:"));
+    }
+
+    #[test]
+    fn test_output_formats() {
+        let mut analytics = FootballAnalytics::new();
+        analytics.add_match(MatchData::new(
+            date(2024, 1, 1),
+            "Home, FC".to_string(),
+            "Away".to_string(),
+            1,
+            0,
+            vec![Goal::new("Striker".to_string(), 10, GoalKind::OpenPlay)],
+            vec![],
+        ));
+
+        let mut csv = Vec::new();
+        table(&analytics).write(Format::Csv, &mut csv).unwrap();
+        let csv = String::from_utf8(csv).unwrap();
+        assert_eq!(csv.lines().nth(1), Some("1,\"Home, FC\",1,1,0,0,1,0,1,3"));
+
+        let mut json = Vec::new();
+        scorers(&analytics, 5).write(Format::Json, &mut json).unwrap();
+        let parsed: Value = serde_json::from_slice(&json).unwrap();
+        assert_eq!(parsed[0]["player"], "Striker");
+        assert_eq!(parsed[0]["goals"], 1);
+
+        assert!(matches!(player(&[], &analytics, "Nobody"), Err(CliError::NotFound(_))));
+    }
+
+    // A dataset file of its own under the temp directory, removed on drop
+    struct TempDataset(PathBuf);
+
+    impl TempDataset {
+        fn new(name: &str) -> Self {
+            let dir = std::env::temp_dir().join(format!("football_analytics_cli_{}_{}", std::process::id(), name));
+            std::fs::create_dir_all(&dir).unwrap();
+            TempDataset(dir)
+        }
+
+        fn path(&self, file: &str) -> PathBuf {
+            self.0.join(file)
+        }
+
+        fn run(&self, command: &[&str]) -> Result<String, CliError> {
+            let data = self.path("football.json");
+            let mut argv: Vec<String> = command.iter().map(|a| a.to_string()).collect();
+            argv.extend(["--data".to_string(), data.display().to_string()]);
+            let mut out = Vec::new();
+            run(&parse_args(&argv)?, &mut out)?;
+            Ok(String::from_utf8(out).unwrap())
+        }
+    }
+
+    impl Drop for TempDataset {
+        fn drop(&mut self) {
+            let _ = std::fs::remove_dir_all(&self.0);
+        }
+    }
+
+    #[test]
+    fn test_commands_on_a_dataset() {
+        let dir = TempDataset::new("commands");
+        let mut barcelona = Team::new("FC Barcelona".to_string(), "Barcelona".to_string());
+        barcelona.add_player(test_util::player("Lionel Messi", 10, Position::Forward, 91)).unwrap();
+        Dataset::new(vec![barcelona], Vec::new()).save(dir.path("football.json")).unwrap();
+        std::fs::write(
+            dir.path("results.csv"),
+            "Date,HomeTeam,AwayTeam,FTHG,FTAG,HomeScorers,AwayScorers\n\
+             13/08/2023,FC Barcelona,Real Madrid,2,1,Messi 10'; Messi 80',Vinicius 50'\n\
+             20/08/2023,Sevilla,Valencia,0,0,,\n",
+        )
+        .unwrap();
+
+        let csv = dir.path("results.csv").display().to_string();
+        let report = dir.run(&["import", &csv, "--competition", "La Liga", "--season", "2023/24"]).unwrap();
+        assert!(report.contains("now holds 2 matches"), "{}", report);
+        let saved = Dataset::load(dir.path("football.json")).unwrap();
+        assert_eq!(saved.teams[0].players().len(), 1);
+        assert_eq!(saved.matches[0].competition.as_deref(), Some("La Liga"));
+        assert_eq!(saved.matches[0].season, Season::new(2023));
+
+        let squad = dir.run(&["team", "fc barcelona"]).unwrap();
+        assert!(squad.starts_with("FC Barcelona (Barcelona) - P1 W1 D0 L0 GF2 GA1"), "{}", squad);
+        assert!(squad.contains("Lionel Messi"));
+
+        assert!(matches!(dir.run(&["h2h", "FC Barcelona", "Sevilla"]), Err(CliError::NotFound(_))));
+
+        let messi: Value = serde_json::from_str(&dir.run(&["player", "Messi", "--format", "json"]).unwrap()).unwrap();
+        assert_eq!(messi[0]["player"], "Lionel Messi");
+        assert_eq!(messi[0]["team"], "FC Barcelona");
+        assert_eq!(messi[0]["goals"], 2);
+
+        assert!(matches!(dir.run(&["demo"]), Err(CliError::Usage(_))));
+    }
+}
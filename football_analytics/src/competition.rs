@@ -0,0 +1,196 @@
+use std::fmt;
+
+use serde::de::{self, Deserializer};
+use serde::{Deserialize, Serialize, Serializer};
+
+use crate::date::{DateRange, MatchDate, MAX_YEAR};
+use crate::match_data::MatchData;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum CompetitionKind {
+    League,
+    Cup,
+}
+
+// Matches refer to a competition by name
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Competition {
+    pub name: String,
+    pub kind: CompetitionKind,
+    pub country: Option<String>,    // None for international competitions
+}
+
+// A European-style season running from July to June, named after the year
+// it starts in: 2023 is "2023/24". Both of its years are valid `MatchDate`
+// years, so it always has a date range.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Season(u16);
+
+// Which matches a query looks at; `None` means any
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Scope {
+    pub competition: Option<String>,
+    pub season: Option<Season>,
+}
+
+impl Competition {
+    pub fn new(name: String, kind: CompetitionKind, country: Option<String>) -> Self {
+        Competition { name, kind, country }
+    }
+}
+
+impl Season {
+    // None unless the season starts and ends within 1 to `MAX_YEAR`
+    pub fn new(start_year: u16) -> Option<Self> {
+        if (1..MAX_YEAR).contains(&start_year) {
+            Some(Season(start_year))
+        } else {
+            None
+        }
+    }
+
+    pub fn start_year(&self) -> u16 {
+        self.0
+    }
+
+    // The season a date falls in; None for the first half of year 1 and the
+    // second half of `MAX_YEAR`, whose seasons can't be represented
+    pub fn containing(date: MatchDate) -> Option<Self> {
+        if date.month >= 7 {
+            Season::new(date.year)
+        } else {
+            Season::new(date.year.checked_sub(1)?)
+        }
+    }
+
+    // "2023/24", "2023-24" or just "2023"
+    pub fn parse(s: &str) -> Option<Self> {
+        let s = s.trim();
+        let (start, end) = match s.split_once(['/', '-']) {
+            Some((start, end)) => (start, Some(end)),
+            None => (s, None),
+        };
+        let season = Season::new(start.parse().ok()?)?;
+        if let Some(end) = end {
+            let end: u16 = end.parse().ok()?;
+            let next = season.0 + 1;
+            if end != next % 100 && end != next {
+                return None;
+            }
+        }
+        Some(season)
+    }
+
+    pub fn range(&self) -> DateRange {
+        DateRange::season(self.0).expect("seasons only hold valid years")
+    }
+}
+
+impl Scope {
+    pub fn all() -> Self {
+        Scope::default()
+    }
+
+    pub fn competition(name: &str) -> Self {
+        Scope {
+            competition: Some(name.to_string()),
+            season: None,
+        }
+    }
+
+    pub fn season(season: Season) -> Self {
+        Scope {
+            competition: None,
+            season: Some(season),
+        }
+    }
+
+    pub fn with_season(mut self, season: Season) -> Self {
+        self.season = Some(season);
+        self
+    }
+
+    pub fn contains(&self, m: &MatchData) -> bool {
+        let competition_matches = self
+            .competition
+            .as_ref()
+            .is_none_or(|c| m.competition.as_ref() == Some(c));
+        competition_matches && self.season.is_none_or(|s| m.season() == Some(s))
+    }
+}
+
+impl fmt::Display for Season {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}/{:02}", self.0, (self.0 + 1) % 100)
+    }
+}
+
+impl fmt::Display for Scope {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match (&self.competition, self.season) {
+            (Some(c), Some(s)) => write!(f, "{} {}", c, s),
+            (Some(c), None) => write!(f, "{}", c),
+            (None, Some(s)) => write!(f, "{}", s),
+            (None, None) => write!(f, "all competitions"),
+        }
+    }
+}
+
+// Stored as "2023/24"
+impl Serialize for Season {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+impl<'de> Deserialize<'de> for Season {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let s = String::deserialize(deserializer)?;
+        Season::parse(&s).ok_or_else(|| de::Error::custom(format!("invalid season '{}'", s)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::{date, result_on};
+
+    #[test]
+    fn test_seasons_and_scopes() {
+        assert_eq!(Season::parse("2023/24"), Some(Season(2023)));
+        assert_eq!(Season::parse("1999-00"), Some(Season(1999)));
+        assert_eq!(Season::parse("2023/25"), None);
+        assert_eq!(Season(1999).to_string(), "1999/00");
+        assert_eq!(Season::containing(date(2024, 5, 1)), Season::new(2023));
+
+        let league = result_on(date(2024, 5, 1), "A", "B", 0, 0).with_competition("La Liga", None);
+        // An explicit season wins over the date
+        let calendar_year = league.clone().with_competition("MLS", Some(Season(2024)));
+
+        assert!(Scope::competition("La Liga").with_season(Season(2023)).contains(&league));
+        assert!(!Scope::competition("Copa del Rey").contains(&league));
+        assert!(Scope::season(Season(2024)).contains(&calendar_year));
+        assert!(Scope::all().contains(&calendar_year));
+    }
+
+    #[test]
+    fn test_out_of_range_seasons() {
+        assert_eq!(Season::parse("65535/00"), None);
+        assert_eq!(Season::parse("65535"), None);
+        assert_eq!(Season::parse("10000/01"), None);
+        assert_eq!(Season::parse("0/01"), None);
+        assert_eq!(Season::parse("9998/99"), Season::new(9998));
+        assert_eq!(Season::new(MAX_YEAR), None);
+        assert_eq!(Season::new(0), None);
+        assert_eq!(MatchDate::parse("01/01/0000"), None);
+        assert_eq!(MatchDate::parse("10000-01-01"), None);
+
+        let last = Season::new(MAX_YEAR - 1).unwrap();
+        assert_eq!(last.to_string(), "9998/99");
+        assert_eq!(last.range().to, Some(date(MAX_YEAR, 6, 30)));
+        assert_eq!(Season::containing(date(1, 3, 1)), None);
+        assert_eq!(Season::containing(date(MAX_YEAR, 8, 1)), None);
+        assert_eq!(Season::containing(date(MAX_YEAR, 5, 1)), Some(last));
+        assert_eq!(DateRange::season(MAX_YEAR), None);
+    }
+}
@@ -0,0 +1,226 @@
+use std::fmt;
+
+use serde::de::{self, Deserializer};
+use serde::{Deserialize, Serialize, Serializer};
+
+// Latest year a `MatchDate` can have; dates are written with four digits
+pub const MAX_YEAR: u16 = 9999;
+
+// Calendar date of a match. Field order matters: the derived `Ord`
+// compares year, then month, then day.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct MatchDate {
+    pub year: u16,
+    pub month: u8,
+    pub day: u8,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct KickoffTime {
+    pub hour: u8,
+    pub minute: u8,
+}
+
+// Inclusive range of dates; `None` leaves that end open
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct DateRange {
+    pub from: Option<MatchDate>,
+    pub to: Option<MatchDate>,
+}
+
+impl MatchDate {
+    // Years run from 1 to `MAX_YEAR`
+    pub fn new(year: u16, month: u8, day: u8) -> Option<Self> {
+        if year == 0 || year > MAX_YEAR || month == 0 || month > 12 || day == 0 || day > days_in_month(year, month) {
+            return None;
+        }
+        Some(MatchDate { year, month, day })
+    }
+
+    // Accepts ISO dates (2023-08-13) and the day-first formats used by
+    // football-data.co.uk (13/08/2023 and 13/08/23).
+    pub fn parse(s: &str) -> Option<Self> {
+        let s = s.trim();
+
+        if let Some((year, rest)) = s.split_once('-') {
+            let (month, day) = rest.split_once('-')?;
+            return MatchDate::new(year.parse().ok()?, month.parse().ok()?, day.parse().ok()?);
+        }
+
+        let mut parts = s.split('/');
+        let day = parts.next()?.parse().ok()?;
+        let month = parts.next()?.parse().ok()?;
+        let year_str = parts.next()?;
+        if parts.next().is_some() {
+            return None;
+        }
+
+        let year: u16 = year_str.parse().ok()?;
+        let year = match year_str.len() {
+            2 if year < 50 => 2000 + year,
+            2 => 1900 + year,
+            4 => year,
+            _ => return None,
+        };
+        MatchDate::new(year, month, day)
+    }
+}
+
+impl KickoffTime {
+    pub fn new(hour: u8, minute: u8) -> Option<Self> {
+        if hour > 23 || minute > 59 {
+            return None;
+        }
+        Some(KickoffTime { hour, minute })
+    }
+
+    // "15:00" style
+    pub fn parse(s: &str) -> Option<Self> {
+        let (hour, minute) = s.trim().split_once(':')?;
+        KickoffTime::new(hour.parse().ok()?, minute.parse().ok()?)
+    }
+}
+
+impl DateRange {
+    pub fn all() -> Self {
+        DateRange { from: None, to: None }
+    }
+
+    pub fn between(from: MatchDate, to: MatchDate) -> Self {
+        DateRange { from: Some(from), to: Some(to) }
+    }
+
+    pub fn since(from: MatchDate) -> Self {
+        DateRange { from: Some(from), to: None }
+    }
+
+    pub fn until(to: MatchDate) -> Self {
+        DateRange { from: None, to: Some(to) }
+    }
+
+    pub fn month(year: u16, month: u8) -> Option<Self> {
+        let first = MatchDate::new(year, month, 1)?;
+        let last = MatchDate::new(year, month, days_in_month(year, month))?;
+        Some(DateRange::between(first, last))
+    }
+
+    // A European season running from 1 July to 30 June, e.g. 2023 => 2023/24.
+    // None if either year is out of range.
+    pub fn season(start_year: u16) -> Option<Self> {
+        let first = MatchDate::new(start_year, 7, 1)?;
+        let last = MatchDate::new(start_year.checked_add(1)?, 6, 30)?;
+        Some(DateRange::between(first, last))
+    }
+
+    pub fn contains(&self, date: MatchDate) -> bool {
+        self.from.is_none_or(|from| date >= from) && self.to.is_none_or(|to| date <= to)
+    }
+}
+
+fn days_in_month(year: u16, month: u8) -> u8 {
+    match month {
+        4 | 6 | 9 | 11 => 30,
+        2 if (year.is_multiple_of(4) && !year.is_multiple_of(100)) || year.is_multiple_of(400) => 29,
+        2 => 28,
+        _ => 31,
+    }
+}
+
+impl fmt::Display for MatchDate {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:04}-{:02}-{:02}", self.year, self.month, self.day)
+    }
+}
+
+impl fmt::Display for KickoffTime {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:02}:{:02}", self.hour, self.minute)
+    }
+}
+
+// Dates and kickoff times are stored as "2024-04-21" and "21:00" strings
+impl Serialize for MatchDate {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+impl<'de> Deserialize<'de> for MatchDate {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let s = String::deserialize(deserializer)?;
+        MatchDate::parse(&s).ok_or_else(|| de::Error::custom(format!("invalid date '{}'", s)))
+    }
+}
+
+impl Serialize for KickoffTime {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+impl<'de> Deserialize<'de> for KickoffTime {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let s = String::deserialize(deserializer)?;
+        KickoffTime::parse(&s).ok_or_else(|| de::Error::custom(format!("invalid kickoff time '{}'", s)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::analytics::FootballAnalytics;
+    use crate::test_util::{date, result_on};
+
+    #[test]
+    fn test_parse_dates() {
+        assert_eq!(MatchDate::parse("2023-08-13"), Some(date(2023, 8, 13)));
+        assert_eq!(MatchDate::parse(" 13/08/2023 "), Some(date(2023, 8, 13)));
+        assert_eq!(MatchDate::parse("13/08/23"), Some(date(2023, 8, 13)));
+        assert_eq!(MatchDate::parse("13/08/49"), Some(date(2049, 8, 13)));
+        assert_eq!(MatchDate::parse("13/08/50"), Some(date(1950, 8, 13)));
+        assert_eq!(MatchDate::parse("13/08/023"), None);
+        assert_eq!(MatchDate::parse("31/04/2024"), None);
+        assert_eq!(MatchDate::parse("2024-13-01"), None);
+
+        assert_eq!(MatchDate::parse("29/02/1900"), None);
+        assert_eq!(MatchDate::parse("29/02/2000"), Some(date(2000, 2, 29)));
+        assert_eq!(MatchDate::parse("29/02/2023"), None);
+        assert_eq!(MatchDate::parse("2024-02-29"), Some(date(2024, 2, 29)));
+    }
+
+    #[test]
+    fn test_parse_kickoff_times() {
+        assert_eq!(KickoffTime::parse("15:00"), KickoffTime::new(15, 0));
+        assert_eq!(KickoffTime::parse(" 9:30 "), KickoffTime::new(9, 30));
+        assert_eq!(KickoffTime::parse("24:00"), None);
+        assert_eq!(KickoffTime::parse("20:60"), None);
+        assert_eq!(KickoffTime::parse("2000"), None);
+    }
+
+    #[test]
+    fn test_ranges_include_both_ends() {
+        let february = DateRange::month(2024, 2).unwrap();
+        assert_eq!(february, DateRange::between(date(2024, 2, 1), date(2024, 2, 29)));
+        assert!(february.contains(date(2024, 2, 1)) && february.contains(date(2024, 2, 29)));
+        assert!(!february.contains(date(2024, 1, 31)) && !february.contains(date(2024, 3, 1)));
+        assert_eq!(DateRange::month(2024, 13), None);
+
+        let since = DateRange::since(date(2024, 1, 1));
+        assert!(since.contains(date(2024, 1, 1)) && !since.contains(date(2023, 12, 31)));
+        let until = DateRange::until(date(2024, 1, 1));
+        assert!(until.contains(date(2024, 1, 1)) && !until.contains(date(2024, 1, 2)));
+        assert!(DateRange::all().contains(date(1, 1, 1)));
+    }
+
+    #[test]
+    fn test_untimed_matches_come_first_on_their_day() {
+        let mut analytics = FootballAnalytics::new();
+        let evening = result_on(date(2024, 3, 2), "A", "B", 1, 0).with_kickoff(KickoffTime::new(20, 0).unwrap());
+        let untimed = result_on(date(2024, 3, 2), "C", "D", 0, 0);
+        analytics.add_match(result_on(date(2024, 3, 3), "E", "F", 2, 2));
+        analytics.add_match(evening.clone());
+        analytics.add_match(untimed.clone());
+
+        assert_eq!(analytics.matches()[..2], [untimed, evening]);
+    }
+}
@@ -0,0 +1,260 @@
+use std::collections::HashMap;
+use std::fmt;
+
+use crate::events::{Card, CardKind};
+use crate::fixtures::Fixture;
+use crate::match_data::MatchData;
+
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct DisciplineRecord {
+    pub yellows: usize,
+    pub second_yellows: usize,
+    pub reds: usize,
+}
+
+// When players miss matches. Bans are served in the team's next matches,
+// whatever the opponent.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SuspensionRules {
+    pub yellow_limit: Option<usize>,    // Ban after every this many yellows
+    pub yellow_ban: usize,
+    pub second_yellow_ban: usize,
+    pub red_ban: usize,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum SuspensionReason {
+    YellowAccumulation,
+    SecondYellow,
+    Red,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Suspension {
+    pub player: String,
+    pub team: String,
+    pub reason: SuspensionReason,
+    pub matches_remaining: usize,
+}
+
+impl DisciplineRecord {
+    pub fn add(&mut self, card: &Card) {
+        match card.kind {
+            CardKind::Yellow => self.yellows += 1,
+            CardKind::SecondYellow => self.second_yellows += 1,
+            CardKind::Red => self.reds += 1,
+        }
+    }
+
+    pub fn sendings_off(&self) -> usize {
+        self.second_yellows + self.reds
+    }
+
+    // Fair play points: 1 per yellow, 3 per second yellow, 4 per straight red
+    pub fn points(&self) -> usize {
+        self.yellows + self.second_yellows * 3 + self.reds * 4
+    }
+}
+
+impl Default for SuspensionRules {
+    fn default() -> Self {
+        SuspensionRules {
+            yellow_limit: Some(5),
+            yellow_ban: 1,
+            second_yellow_ban: 1,
+            red_ban: 1,
+        }
+    }
+}
+
+impl SuspensionRules {
+    pub fn new(yellow_limit: Option<usize>, yellow_ban: usize, second_yellow_ban: usize, red_ban: usize) -> Self {
+        SuspensionRules {
+            yellow_limit,
+            yellow_ban,
+            second_yellow_ban,
+            red_ban,
+        }
+    }
+
+    // Bans still to be served after `matches`, which must be in date order.
+    // A player's own team playing a match is what counts a ban down. The
+    // first booking of a two-yellow sending off doesn't count towards the
+    // yellow limit.
+    pub fn suspensions<'a, I>(&self, matches: I) -> Vec<Suspension>
+    where
+        I: IntoIterator<Item = &'a MatchData>,
+    {
+        let mut yellows: HashMap<(String, String), usize> = HashMap::new();
+        let mut bans: Vec<Suspension> = Vec::new();
+
+        for m in matches {
+            // Serve bans before handing out new ones from this match
+            for ban in bans.iter_mut() {
+                if m.involves(&ban.team) && ban.matches_remaining > 0 {
+                    ban.matches_remaining -= 1;
+                }
+            }
+            bans.retain(|b| b.matches_remaining > 0);
+
+            for (team, cards) in [(&m.home_team, &m.home_cards), (&m.away_team, &m.away_cards)] {
+                for card in cards {
+                    let (reason, length) = match card.kind {
+                        CardKind::Red => (SuspensionReason::Red, self.red_ban),
+                        CardKind::SecondYellow => (SuspensionReason::SecondYellow, self.second_yellow_ban),
+                        CardKind::Yellow => {
+                            let sent_off = cards
+                                .iter()
+                                .any(|c| c.player == card.player && c.kind == CardKind::SecondYellow);
+                            if sent_off {
+                                continue;
+                            }
+                            let count = yellows.entry((team.clone(), card.player.clone())).or_insert(0);
+                            *count += 1;
+                            match self.yellow_limit {
+                                Some(limit) if limit > 0 && count.is_multiple_of(limit) => {
+                                    (SuspensionReason::YellowAccumulation, self.yellow_ban)
+                                }
+                                _ => continue,
+                            }
+                        }
+                    };
+                    if length > 0 {
+                        bans.push(Suspension {
+                            player: card.player.clone(),
+                            team: team.clone(),
+                            reason,
+                            matches_remaining: length,
+                        });
+                    }
+                }
+            }
+        }
+
+        bans
+    }
+
+    // Players from either side who would miss `fixture` if it were played next
+    pub fn unavailable_for<'a, I>(&self, fixture: &Fixture, matches: I) -> Vec<Suspension>
+    where
+        I: IntoIterator<Item = &'a MatchData>,
+    {
+        self.suspensions(matches)
+            .into_iter()
+            .filter(|s| s.team == fixture.home_team || s.team == fixture.away_team)
+            .collect()
+    }
+}
+
+// Keyed by player name
+pub fn player_discipline<'a, I>(matches: I) -> HashMap<String, DisciplineRecord>
+where
+    I: IntoIterator<Item = &'a MatchData>,
+{
+    let mut records: HashMap<String, DisciplineRecord> = HashMap::new();
+    for m in matches {
+        for card in m.home_cards.iter().chain(m.away_cards.iter()) {
+            records.entry(card.player.clone()).or_default().add(card);
+        }
+    }
+    records
+}
+
+// Keyed by team name
+pub fn team_discipline<'a, I>(matches: I) -> HashMap<String, DisciplineRecord>
+where
+    I: IntoIterator<Item = &'a MatchData>,
+{
+    let mut records: HashMap<String, DisciplineRecord> = HashMap::new();
+    for m in matches {
+        for (team, cards) in [(&m.home_team, &m.home_cards), (&m.away_team, &m.away_cards)] {
+            let record = records.entry(team.clone()).or_default();
+            for card in cards {
+                record.add(card);
+            }
+        }
+    }
+    records
+}
+
+// Worst offenders first
+pub fn discipline_table(records: &HashMap<String, DisciplineRecord>) -> Vec<(String, DisciplineRecord)> {
+    let mut table: Vec<(String, DisciplineRecord)> = records.iter().map(|(k, v)| (k.clone(), *v)).collect();
+    table.sort_by(|a, b| b.1.points().cmp(&a.1.points()).then_with(|| a.0.cmp(&b.0)));
+    table
+}
+
+impl fmt::Display for SuspensionReason {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SuspensionReason::YellowAccumulation => write!(f, "yellow card accumulation"),
+            SuspensionReason::SecondYellow => write!(f, "second yellow"),
+            SuspensionReason::Red => write!(f, "red card"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::{date, result_on};
+
+    fn game(day: u8, home: &str, away: &str, home_cards: Vec<Card>, away_cards: Vec<Card>) -> MatchData {
+        result_on(date(2024, 1, day), home, away, 0, 0).with_cards(home_cards, away_cards)
+    }
+
+    fn card(player: &str, kind: CardKind) -> Card {
+        Card::new(player.to_string(), 50, kind)
+    }
+
+    #[test]
+    fn test_yellow_accumulation_and_serving_bans() {
+        let rules = SuspensionRules::new(Some(2), 1, 1, 2);
+        let matches = vec![
+            game(1, "A", "B", vec![card("Hard Man", CardKind::Yellow)], vec![card("Striker", CardKind::Red)]),
+            game(8, "C", "A", vec![], vec![card("Hard Man", CardKind::Yellow)]),
+        ];
+
+        let bans = rules.suspensions(&matches);
+        assert_eq!(bans.len(), 2);
+        let striker = bans.iter().find(|b| b.player == "Striker").unwrap();
+        // B hasn't played since the red
+        assert_eq!(striker.matches_remaining, 2);
+        let hard_man = bans.iter().find(|b| b.player == "Hard Man").unwrap();
+        assert_eq!(hard_man.reason, SuspensionReason::YellowAccumulation);
+
+        let fixture = Fixture {
+            matchday: 3,
+            home_team: "B".to_string(),
+            away_team: "C".to_string(),
+        };
+        let unavailable = rules.unavailable_for(&fixture, &matches);
+        assert_eq!(unavailable.len(), 1);
+        assert_eq!(unavailable[0].player, "Striker");
+
+        let mut played = matches.clone();
+        played.push(game(15, "B", "D", vec![], vec![]));
+        let bans = rules.suspensions(&played);
+        assert_eq!(bans.iter().find(|b| b.player == "Striker").unwrap().matches_remaining, 1);
+    }
+
+    #[test]
+    fn test_second_yellow_booking_does_not_accumulate() {
+        let rules = SuspensionRules::new(Some(1), 1, 1, 1);
+        let matches = vec![game(
+            1,
+            "A",
+            "B",
+            vec![card("Rash", CardKind::Yellow), card("Rash", CardKind::SecondYellow)],
+            vec![],
+        )];
+
+        let bans = rules.suspensions(&matches);
+        assert_eq!(bans.len(), 1);
+        assert_eq!(bans[0].reason, SuspensionReason::SecondYellow);
+
+        let records = player_discipline(&matches);
+        assert_eq!(records["Rash"].points(), 4);
+        assert_eq!(team_discipline(&matches)["B"], DisciplineRecord::default());
+    }
+}
@@ -0,0 +1,217 @@
+use std::collections::HashMap;
+
+use crate::date::MatchDate;
+use crate::match_data::MatchData;
+
+// Elo ratings in the style of the World Football Elo Ratings: the home side
+// gets a bonus when computing expected results and bigger wins move the
+// ratings further.
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct EloConfig {
+    pub initial_rating: f64,
+    pub k_factor: f64,
+    pub home_advantage: f64,    // Rating points added to the home side
+}
+
+impl Default for EloConfig {
+    fn default() -> Self {
+        EloConfig {
+            initial_rating: 1500.0,
+            k_factor: 20.0,
+            home_advantage: 100.0,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct RatingPoint {
+    pub match_index: usize,     // Position of the match in the replayed history
+    pub date: MatchDate,
+    pub opponent: String,
+    pub rating: f64,            // Rating after the match
+}
+
+#[derive(Debug, Clone)]
+pub struct EloRatings {
+    config: EloConfig,
+    ratings: HashMap<String, f64>,
+    history: HashMap<String, Vec<RatingPoint>>,
+    matches_played: usize,
+}
+
+impl EloRatings {
+    pub fn new(config: EloConfig) -> Self {
+        EloRatings {
+            config,
+            ratings: HashMap::new(),
+            history: HashMap::new(),
+            matches_played: 0,
+        }
+    }
+
+    // Replays `matches` in the order given, which should be chronological
+    pub fn from_matches(matches: &[MatchData], config: EloConfig) -> Self {
+        let mut elo = EloRatings::new(config);
+        for m in matches {
+            elo.update(m);
+        }
+        elo
+    }
+
+    pub fn update(&mut self, m: &MatchData) {
+        let home_rating = self.rating_or_initial(&m.home_team);
+        let away_rating = self.rating_or_initial(&m.away_team);
+
+        let expected_home = self.expected_home_score(home_rating, away_rating);
+        let actual_home = if m.home_score > m.away_score {
+            1.0
+        } else if m.home_score == m.away_score {
+            0.5
+        } else {
+            0.0
+        };
+
+        let margin = m.home_score.abs_diff(m.away_score);
+        let change = self.config.k_factor * goal_margin_multiplier(margin) * (actual_home - expected_home);
+
+        let match_index = self.matches_played;
+        self.matches_played += 1;
+        self.set_rating(&m.home_team, &m.away_team, home_rating + change, match_index, m.date);
+        self.set_rating(&m.away_team, &m.home_team, away_rating - change, match_index, m.date);
+    }
+
+    pub fn rating(&self, team: &str) -> Option<f64> {
+        self.ratings.get(team).copied()
+    }
+
+    pub fn history(&self, team: &str) -> &[RatingPoint] {
+        self.history.get(team).map(|h| h.as_slice()).unwrap_or(&[])
+    }
+
+    // Highest rated team first
+    pub fn rankings(&self) -> Vec<(String, f64)> {
+        let mut ranked: Vec<(String, f64)> = self.ratings.iter().map(|(t, r)| (t.clone(), *r)).collect();
+        ranked.sort_by(|a, b| b.1.total_cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+        ranked
+    }
+
+    // Rating gained or lost over a team's last `n` matches
+    pub fn recent_change(&self, team: &str, n: usize) -> f64 {
+        let history = self.history(team);
+        if history.is_empty() || n == 0 {
+            return 0.0;
+        }
+
+        let current = history[history.len() - 1].rating;
+        let before = if n >= history.len() {
+            self.config.initial_rating
+        } else {
+            history[history.len() - n - 1].rating
+        };
+        current - before
+    }
+
+    // Expected score (win = 1, draw = 0.5) for `home` against `away`
+    pub fn expected_score(&self, home: &str, away: &str) -> f64 {
+        self.expected_home_score(self.rating_or_initial(home), self.rating_or_initial(away))
+    }
+
+    fn expected_home_score(&self, home_rating: f64, away_rating: f64) -> f64 {
+        let diff = away_rating - (home_rating + self.config.home_advantage);
+        1.0 / (1.0 + 10f64.powf(diff / 400.0))
+    }
+
+    fn rating_or_initial(&self, team: &str) -> f64 {
+        self.rating(team).unwrap_or(self.config.initial_rating)
+    }
+
+    fn set_rating(&mut self, team: &str, opponent: &str, rating: f64, match_index: usize, date: MatchDate) {
+        self.ratings.insert(team.to_string(), rating);
+        self.history.entry(team.to_string()).or_default().push(RatingPoint {
+            match_index,
+            date,
+            opponent: opponent.to_string(),
+            rating,
+        });
+    }
+}
+
+// A one-goal win counts normally, a two-goal win 1.5x and bigger wins
+// a little more for every extra goal.
+fn goal_margin_multiplier(margin: u8) -> f64 {
+    match margin {
+        0 | 1 => 1.0,
+        2 => 1.5,
+        n => (11.0 + n as f64) / 8.0,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::result;
+
+    fn neutral() -> EloConfig {
+        EloConfig {
+            home_advantage: 0.0,
+            ..EloConfig::default()
+        }
+    }
+
+    fn change_after(home_score: u8, away_score: u8, config: EloConfig) -> f64 {
+        let elo = EloRatings::from_matches(&[result("A", "B", home_score, away_score)], config);
+        elo.rating("A").unwrap() - 1500.0
+    }
+
+    #[test]
+    fn test_expected_score_and_exchange() {
+        let elo = EloRatings::new(neutral());
+        assert_eq!(elo.expected_score("A", "B"), 0.5);
+
+        let elo = EloRatings::from_matches(&[result("A", "B", 1, 0)], neutral());
+        // 400 points apart means 10:1 odds
+        let favourite = 1.0 / (1.0 + 10f64.powf(-400.0 / 400.0));
+        assert!((elo.expected_home_score(1900.0, 1500.0) - favourite).abs() < 1e-12);
+
+        // A 1-0 between equals moves k/2 points from loser to winner
+        assert_eq!(elo.rating("A"), Some(1510.0));
+        assert_eq!(elo.rating("B"), Some(1490.0));
+    }
+
+    #[test]
+    fn test_home_advantage_and_draws() {
+        let elo = EloRatings::new(EloConfig::default());
+        assert!(elo.expected_score("A", "B") > 0.6);
+
+        assert_eq!(change_after(1, 1, neutral()), 0.0);
+        // The home side was expected to do better than a draw
+        assert!(change_after(1, 1, EloConfig::default()) < 0.0);
+        assert!(change_after(1, 0, EloConfig::default()) < change_after(1, 0, neutral()));
+    }
+
+    #[test]
+    fn test_goal_margin_multiplier() {
+        assert_eq!(goal_margin_multiplier(1), 1.0);
+        assert_eq!(goal_margin_multiplier(2), 1.5);
+        assert_eq!(goal_margin_multiplier(3), 1.75);
+        assert_eq!(change_after(3, 0, neutral()), 1.75 * change_after(1, 0, neutral()));
+    }
+
+    #[test]
+    fn test_recent_change_and_rankings() {
+        let matches = vec![result("A", "B", 2, 0), result("C", "A", 1, 0), result("B", "C", 0, 0)];
+        let elo = EloRatings::from_matches(&matches, neutral());
+
+        assert_eq!(elo.history("A").len(), 2);
+        let a = elo.rating("A").unwrap();
+        assert_eq!(elo.recent_change("A", 5), a - 1500.0);
+        assert_eq!(elo.recent_change("A", 1), a - elo.history("A")[0].rating);
+        assert_eq!(elo.recent_change("A", 0), 0.0);
+
+        // C beat A while A was the higher rated side
+        let rankings = elo.rankings();
+        let order: Vec<&str> = rankings.iter().map(|(team, _)| team.as_str()).collect();
+        assert_eq!(order, vec!["C", "A", "B"]);
+    }
+}
@@ -0,0 +1,313 @@
+use std::fmt;
+
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum GoalKind {
+    OpenPlay,
+    Penalty,
+    OwnGoal,
+    FreeKick,
+}
+
+// A goal is stored under the side it counts for, so an own goal scored by
+// an away defender sits in `MatchData::home_goals`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Goal {
+    #[serde(default)]
+    pub minute: Option<u8>,         // None when the source only names the scorer
+    #[serde(default)]
+    pub added_time: u8,             // Stoppage time: 45+2' is minute 45, added_time 2
+    pub scorer: String,             // For own goals, the player who put it in his own net
+    pub assist: Option<String>,
+    pub kind: GoalKind,
+}
+
+impl Goal {
+    pub fn new(scorer: String, minute: u8, kind: GoalKind) -> Self {
+        Goal {
+            minute: Some(minute),
+            added_time: 0,
+            scorer,
+            assist: None,
+            kind,
+        }
+    }
+
+    pub fn with_assist(mut self, assist: String) -> Self {
+        self.assist = Some(assist);
+        self
+    }
+
+    pub fn with_added_time(mut self, added_time: u8) -> Self {
+        self.added_time = added_time;
+        self
+    }
+
+    pub fn is_own_goal(&self) -> bool {
+        self.kind == GoalKind::OwnGoal
+    }
+
+    // A goal whose minute isn't known, as in older scorer lists
+    pub fn untimed(scorer: String, kind: GoalKind) -> Self {
+        Goal {
+            minute: None,
+            ..Goal::new(scorer, 0, kind)
+        }
+    }
+
+    // Sort key: 45+2' comes after 45' but before 46'. Goals without a minute
+    // sort last.
+    pub fn time(&self) -> (u8, u8) {
+        (self.minute.unwrap_or(u8::MAX), self.added_time)
+    }
+
+    // Parses the format written by `Display`:
+    //   "Messi 23'", "Messi 45+2' (pen)", "Ramos 80' (og)", "Busquets 12' (Messi)"
+    // A bracketed pen/og/fk sets the kind, any other bracketed name is the assist.
+    // The minute may be left out ("Messi", "Lionel Messi (pen)"), which is
+    // how scorer lists were written before minutes were recorded.
+    pub fn parse(s: &str) -> Option<Goal> {
+        let s = s.trim();
+        let (main, extras) = match s.find('(') {
+            Some(i) => (s[..i].trim(), &s[i..]),
+            None => (s, ""),
+        };
+
+        let (scorer, time) = match main.rsplit_once(' ') {
+            Some((scorer, last)) => match parse_minute(last) {
+                Some(time) => (scorer.trim(), Some(time)),
+                None => (main, None),
+            },
+            None => match parse_minute(main) {
+                Some(_) => return None,     // A minute with no scorer
+                None => (main, None),
+            },
+        };
+        if scorer.is_empty() {
+            return None;
+        }
+        let mut goal = match time {
+            Some((minute, added_time)) => {
+                Goal::new(scorer.to_string(), minute, GoalKind::OpenPlay).with_added_time(added_time)
+            }
+            None => Goal::untimed(scorer.to_string(), GoalKind::OpenPlay),
+        };
+
+        for extra in extras.split(')') {
+            let extra = extra.trim();
+            if extra.is_empty() {
+                continue;
+            }
+            let extra = extra.strip_prefix('(')?.trim();
+            match extra.to_ascii_lowercase().as_str() {
+                "pen" => goal.kind = GoalKind::Penalty,
+                "og" => goal.kind = GoalKind::OwnGoal,
+                "fk" => goal.kind = GoalKind::FreeKick,
+                "" => return None,
+                _ => goal.assist = Some(extra.to_string()),
+            }
+        }
+
+        Some(goal)
+    }
+}
+
+// "23'", "23" or "45+2'"
+fn parse_minute(s: &str) -> Option<(u8, u8)> {
+    let s = s.trim_end_matches('\'');
+    match s.split_once('+') {
+        Some((m, a)) => Some((m.parse().ok()?, a.parse().ok()?)),
+        None => Some((s.parse().ok()?, 0)),
+    }
+}
+
+impl fmt::Display for GoalKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            GoalKind::OpenPlay => write!(f, "open play"),
+            GoalKind::Penalty => write!(f, "pen"),
+            GoalKind::OwnGoal => write!(f, "og"),
+            GoalKind::FreeKick => write!(f, "fk"),
+        }
+    }
+}
+
+impl fmt::Display for Goal {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.scorer)?;
+        if let Some(minute) = self.minute {
+            write!(f, " {}", minute)?;
+            if self.added_time > 0 {
+                write!(f, "+{}", self.added_time)?;
+            }
+            write!(f, "'")?;
+        }
+        if self.kind != GoalKind::OpenPlay {
+            write!(f, " ({})", self.kind)?;
+        }
+        if let Some(assist) = &self.assist {
+            write!(f, " ({})", assist)?;
+        }
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum BodyPart {
+    RightFoot,
+    LeftFoot,
+    Head,
+    Other,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum ShotSituation {
+    OpenPlay,
+    Counter,
+    SetPiece,       // From a corner or indirect free kick
+    DirectFreeKick,
+    Penalty,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum ShotOutcome {
+    Goal,
+    Saved,
+    Blocked,
+    OffTarget,
+    Woodwork,
+}
+
+// Coordinates are in metres on a 105 x 68 pitch from the shooting team's
+// point of view: x = 105 is the goal line being attacked, y = 34 is the
+// middle of the goal.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Shot {
+    pub minute: u8,
+    pub player: String,
+    pub x: f64,
+    pub y: f64,
+    pub body_part: BodyPart,
+    pub situation: ShotSituation,
+    pub outcome: ShotOutcome,
+}
+
+pub const PITCH_LENGTH: f64 = 105.0;
+pub const PITCH_WIDTH: f64 = 68.0;
+pub const GOAL_WIDTH: f64 = 7.32;
+
+impl Shot {
+    pub fn new(
+        player: String,
+        minute: u8,
+        (x, y): (f64, f64),
+        body_part: BodyPart,
+        situation: ShotSituation,
+        outcome: ShotOutcome,
+    ) -> Self {
+        Shot {
+            minute,
+            player,
+            x,
+            y,
+            body_part,
+            situation,
+            outcome,
+        }
+    }
+
+    // Straight-line distance to the centre of the goal
+    pub fn distance_to_goal(&self) -> f64 {
+        ((PITCH_LENGTH - self.x).powi(2) + (PITCH_WIDTH / 2.0 - self.y).powi(2)).sqrt()
+    }
+
+    // Angle in radians between the lines to each post; wider is easier
+    pub fn goal_angle(&self) -> f64 {
+        let dx = (PITCH_LENGTH - self.x).max(0.0);
+        let left = (self.y - (PITCH_WIDTH - GOAL_WIDTH) / 2.0).atan2(dx);
+        let right = (self.y - (PITCH_WIDTH + GOAL_WIDTH) / 2.0).atan2(dx);
+        (left - right).abs()
+    }
+
+    pub fn is_goal(&self) -> bool {
+        self.outcome == ShotOutcome::Goal
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Substitution {
+    pub minute: u8,
+    pub player_off: String,
+    pub player_on: String,
+}
+
+impl Substitution {
+    pub fn new(player_off: String, player_on: String, minute: u8) -> Self {
+        Substitution {
+            minute,
+            player_off,
+            player_on,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum CardKind {
+    Yellow,
+    SecondYellow,   // The second booking of a match, which means a sending off
+    Red,            // Straight red
+}
+
+// A player sent off for two bookings has a `Yellow` and a `SecondYellow`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Card {
+    pub minute: u8,
+    pub player: String,
+    pub kind: CardKind,
+}
+
+impl Card {
+    pub fn new(player: String, minute: u8, kind: CardKind) -> Self {
+        Card {
+            minute,
+            player,
+            kind,
+        }
+    }
+
+    pub fn is_sending_off(&self) -> bool {
+        matches!(self.kind, CardKind::SecondYellow | CardKind::Red)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_goal() {
+        let goal = Goal::parse("Messi 45+2' (pen) (Alba)").unwrap();
+        assert_eq!((goal.minute, goal.added_time), (Some(45), 2));
+        assert_eq!(goal.kind, GoalKind::Penalty);
+        assert_eq!(goal.assist.as_deref(), Some("Alba"));
+        assert_eq!(Goal::parse(&goal.to_string()), Some(goal));
+
+        let own_goal = Goal::parse("Sergio Ramos 80' (og)").unwrap();
+        assert_eq!(own_goal.scorer, "Sergio Ramos");
+        assert!(own_goal.is_own_goal());
+        assert_eq!(Goal::parse("Busquets 12' (Messi)").unwrap().assist.as_deref(), Some("Messi"));
+
+        assert!(Goal::parse("23'").is_none());
+        assert!(Goal::parse("Messi 23' ()").is_none());
+    }
+
+    #[test]
+    fn test_parse_goal_without_minute() {
+        assert_eq!(Goal::parse("Messi"), Some(Goal::untimed("Messi".to_string(), GoalKind::OpenPlay)));
+        let goal = Goal::parse("Lionel Messi (pen)").unwrap();
+        assert_eq!((goal.scorer.as_str(), goal.minute, goal.kind), ("Lionel Messi", None, GoalKind::Penalty));
+        assert_eq!(goal.to_string(), "Lionel Messi (pen)");
+        assert!(goal.time() > Goal::new("Late".to_string(), 90, GoalKind::OpenPlay).with_added_time(5).time());
+    }
+}
@@ -0,0 +1,206 @@
+use crate::date::MatchDate;
+use crate::events::Goal;
+use crate::match_data::MatchData;
+use crate::team::Team;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum RoundRobin {
+    Single,     // Everyone meets once
+    Double,     // Home and away, second half mirrors the first
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Fixture {
+    pub matchday: usize,
+    pub home_team: String,
+    pub away_team: String,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Matchday {
+    pub number: usize,          // 1-based
+    pub fixtures: Vec<Fixture>,
+    pub bye: Option<String>,    // Team sitting this matchday out (odd team counts)
+}
+
+impl Fixture {
+    // Turns the fixture into a played match
+    pub fn result(
+        &self,
+        date: MatchDate,
+        home_score: u8,
+        away_score: u8,
+        home_goals: Vec<Goal>,
+        away_goals: Vec<Goal>,
+    ) -> MatchData {
+        MatchData::new(
+            date,
+            self.home_team.clone(),
+            self.away_team.clone(),
+            home_score,
+            away_score,
+            home_goals,
+            away_goals,
+        )
+    }
+
+    pub fn is_played_by(&self, m: &MatchData) -> bool {
+        self.home_team == m.home_team && self.away_team == m.away_team
+    }
+}
+
+pub fn round_robin(teams: &[Team], format: RoundRobin) -> Vec<Matchday> {
+    let names: Vec<String> = teams.iter().map(|t| t.name.clone()).collect();
+    round_robin_for_names(&names, format)
+}
+
+// Berger tables via the circle method: the last slot stays put and in
+// round r meets slot r, while slots r+k and r-k pair up around it. Home
+// and away alternate with k, which keeps the number of back-to-back home
+// or away games at the minimum of n - 2. With an odd number of teams the
+// fixed slot is a bye.
+pub fn round_robin_for_names(teams: &[String], format: RoundRobin) -> Vec<Matchday> {
+    let mut slots: Vec<Option<&String>> = teams.iter().map(Some).collect();
+    if slots.len() % 2 == 1 {
+        slots.push(None);
+    }
+
+    let n = slots.len();
+    if n < 2 {
+        return Vec::new();
+    }
+    let rounds = n - 1;
+
+    let mut matchdays = Vec::new();
+    for round in 0..rounds {
+        let mut matchday = Matchday {
+            number: round + 1,
+            fixtures: Vec::new(),
+            bye: None,
+        };
+
+        for k in 0..n / 2 {
+            let (home, away) = if k == 0 {
+                if round % 2 == 0 { (round, n - 1) } else { (n - 1, round) }
+            } else {
+                let up = (round + k) % rounds;
+                let down = (round + rounds - k) % rounds;
+                if k % 2 == 1 { (up, down) } else { (down, up) }
+            };
+
+            match (slots[home], slots[away]) {
+                (Some(home), Some(away)) => matchday.fixtures.push(Fixture {
+                    matchday: round + 1,
+                    home_team: home.clone(),
+                    away_team: away.clone(),
+                }),
+                (Some(team), None) | (None, Some(team)) => matchday.bye = Some(team.clone()),
+                (None, None) => {}
+            }
+        }
+
+        matchdays.push(matchday);
+    }
+
+    if format == RoundRobin::Double {
+        let second_half: Vec<Matchday> = matchdays
+            .iter()
+            .map(|md| Matchday {
+                number: md.number + rounds,
+                fixtures: md
+                    .fixtures
+                    .iter()
+                    .map(|f| Fixture {
+                        matchday: md.number + rounds,
+                        home_team: f.away_team.clone(),
+                        away_team: f.home_team.clone(),
+                    })
+                    .collect(),
+                bye: md.bye.clone(),
+            })
+            .collect();
+        matchdays.extend(second_half);
+    }
+
+    matchdays
+}
+
+// Fixtures with no matching result in `played` yet
+pub fn remaining_fixtures(matchdays: &[Matchday], played: &[MatchData]) -> Vec<Fixture> {
+    matchdays
+        .iter()
+        .flat_map(|md| md.fixtures.iter())
+        .filter(|f| !played.iter().any(|m| f.is_played_by(m)))
+        .cloned()
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::{HashMap, HashSet};
+
+    fn names(n: usize) -> Vec<String> {
+        (1..=n).map(|i| format!("Team {}", i)).collect()
+    }
+
+    #[test]
+    fn test_single_round_robin_pairs_everyone_once() {
+        let teams = names(6);
+        let matchdays = round_robin_for_names(&teams, RoundRobin::Single);
+        assert_eq!(matchdays.len(), 5);
+
+        let mut pairs = HashSet::new();
+        for md in &matchdays {
+            let mut playing = HashSet::new();
+            for f in &md.fixtures {
+                assert!(playing.insert(f.home_team.clone()));
+                assert!(playing.insert(f.away_team.clone()));
+                let mut pair = [f.home_team.clone(), f.away_team.clone()];
+                pair.sort();
+                assert!(pairs.insert(pair));
+            }
+            assert_eq!(playing.len(), 6);
+            assert_eq!(md.bye, None);
+        }
+        assert_eq!(pairs.len(), 15);
+    }
+
+    #[test]
+    fn test_home_and_away_are_balanced() {
+        let teams = names(8);
+        let matchdays = round_robin_for_names(&teams, RoundRobin::Single);
+
+        let mut venues: HashMap<&str, Vec<bool>> = HashMap::new();
+        for md in &matchdays {
+            for f in &md.fixtures {
+                venues.entry(&f.home_team).or_default().push(true);
+                venues.entry(&f.away_team).or_default().push(false);
+            }
+        }
+
+        let mut breaks = 0;
+        for sequence in venues.values() {
+            let home_games = sequence.iter().filter(|h| **h).count();
+            assert!(home_games == 3 || home_games == 4);
+            breaks += sequence.windows(2).filter(|w| w[0] == w[1]).count();
+        }
+        // n - 2 breaks is the minimum possible for n teams
+        assert_eq!(breaks, 6);
+    }
+
+    #[test]
+    fn test_odd_team_count_gets_byes() {
+        let teams = names(5);
+        let matchdays = round_robin_for_names(&teams, RoundRobin::Double);
+        assert_eq!(matchdays.len(), 10);
+
+        let byes: HashSet<String> = matchdays.iter().take(5).filter_map(|md| md.bye.clone()).collect();
+        assert_eq!(byes.len(), 5);
+        assert!(matchdays.iter().all(|md| md.fixtures.len() == 2));
+
+        let first = &matchdays[0].fixtures[0];
+        let mirrored = &matchdays[5].fixtures[0];
+        assert_eq!((&first.home_team, &first.away_team), (&mirrored.away_team, &mirrored.home_team));
+    }
+}
@@ -0,0 +1,253 @@
+use std::fmt;
+
+use crate::date::MatchDate;
+use crate::match_data::MatchData;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Outcome {
+    Win,
+    Draw,
+    Loss,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum StreakKind {
+    Winning,
+    Unbeaten,
+    Losing,
+    Scoring,    // Matches with at least one goal
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Streak {
+    pub kind: StreakKind,
+    pub length: usize,
+    pub from: MatchDate,        // First and last match of the run
+    pub to: MatchDate,
+}
+
+// `current` only holds streaks still running after the team's last match
+#[derive(Debug, Clone, PartialEq)]
+pub struct StreakReport {
+    pub team: String,
+    pub longest_winning: Option<Streak>,
+    pub longest_unbeaten: Option<Streak>,
+    pub longest_losing: Option<Streak>,
+    pub longest_scoring: Option<Streak>,
+    pub current: Vec<Streak>,
+}
+
+impl Outcome {
+    // None if `team` didn't play in the match
+    pub fn for_team(m: &MatchData, team: &str) -> Option<Outcome> {
+        let (scored, conceded) = goals_for_against(m, team)?;
+        Some(if scored > conceded {
+            Outcome::Win
+        } else if scored == conceded {
+            Outcome::Draw
+        } else {
+            Outcome::Loss
+        })
+    }
+
+    pub fn letter(&self) -> char {
+        match self {
+            Outcome::Win => 'W',
+            Outcome::Draw => 'D',
+            Outcome::Loss => 'L',
+        }
+    }
+}
+
+impl StreakKind {
+    pub const ALL: [StreakKind; 4] = [
+        StreakKind::Winning,
+        StreakKind::Unbeaten,
+        StreakKind::Losing,
+        StreakKind::Scoring,
+    ];
+
+    fn extends(&self, m: &MatchData, team: &str) -> bool {
+        let outcome = Outcome::for_team(m, team);
+        match self {
+            StreakKind::Winning => outcome == Some(Outcome::Win),
+            StreakKind::Unbeaten => matches!(outcome, Some(Outcome::Win | Outcome::Draw)),
+            StreakKind::Losing => outcome == Some(Outcome::Loss),
+            StreakKind::Scoring => goals_for_against(m, team).is_some_and(|(scored, _)| scored > 0),
+        }
+    }
+}
+
+impl StreakReport {
+    pub fn longest(&self, kind: StreakKind) -> Option<&Streak> {
+        match kind {
+            StreakKind::Winning => self.longest_winning.as_ref(),
+            StreakKind::Unbeaten => self.longest_unbeaten.as_ref(),
+            StreakKind::Losing => self.longest_losing.as_ref(),
+            StreakKind::Scoring => self.longest_scoring.as_ref(),
+        }
+    }
+
+    pub fn current(&self, kind: StreakKind) -> Option<&Streak> {
+        self.current.iter().find(|s| s.kind == kind)
+    }
+}
+
+// Results of `matches` in order, one letter per match, e.g. "WWDLW".
+// Matches the team didn't play are skipped.
+pub fn form_string<'a, I>(team: &str, matches: I) -> String
+where
+    I: IntoIterator<Item = &'a MatchData>,
+{
+    matches
+        .into_iter()
+        .filter_map(|m| Outcome::for_team(m, team))
+        .map(|o| o.letter())
+        .collect()
+}
+
+// `matches` must be in date order. On equal length the earlier streak counts
+// as the longest.
+pub fn streaks<'a, I>(team: &str, matches: I) -> StreakReport
+where
+    I: IntoIterator<Item = &'a MatchData>,
+{
+    let played: Vec<&MatchData> = matches.into_iter().filter(|m| m.involves(team)).collect();
+
+    let mut longest: Vec<Option<Streak>> = vec![None; StreakKind::ALL.len()];
+    let mut current = Vec::new();
+
+    for (i, kind) in StreakKind::ALL.iter().enumerate() {
+        let mut run: Option<Streak> = None;
+        for m in &played {
+            if kind.extends(m, team) {
+                let streak = run.get_or_insert(Streak {
+                    kind: *kind,
+                    length: 0,
+                    from: m.date,
+                    to: m.date,
+                });
+                streak.length += 1;
+                streak.to = m.date;
+            } else if let Some(finished) = run.take() {
+                keep_longest(&mut longest[i], finished);
+            }
+        }
+        if let Some(running) = run {
+            current.push(running.clone());
+            keep_longest(&mut longest[i], running);
+        }
+    }
+
+    let mut longest = longest.into_iter();
+    StreakReport {
+        team: team.to_string(),
+        longest_winning: longest.next().flatten(),
+        longest_unbeaten: longest.next().flatten(),
+        longest_losing: longest.next().flatten(),
+        longest_scoring: longest.next().flatten(),
+        current,
+    }
+}
+
+fn keep_longest(best: &mut Option<Streak>, candidate: Streak) {
+    if best.as_ref().is_none_or(|b| candidate.length > b.length) {
+        *best = Some(candidate);
+    }
+}
+
+fn goals_for_against(m: &MatchData, team: &str) -> Option<(u8, u8)> {
+    if m.home_team == team {
+        Some((m.home_score, m.away_score))
+    } else if m.away_team == team {
+        Some((m.away_score, m.home_score))
+    } else {
+        None
+    }
+}
+
+impl fmt::Display for StreakKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            StreakKind::Winning => write!(f, "winning"),
+            StreakKind::Unbeaten => write!(f, "unbeaten"),
+            StreakKind::Losing => write!(f, "losing"),
+            StreakKind::Scoring => write!(f, "scoring"),
+        }
+    }
+}
+
+impl fmt::Display for Streak {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} {} ({} to {})", self.length, self.kind, self.from, self.to)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::analytics::FootballAnalytics;
+    use crate::date::DateRange;
+    use crate::test_util::{date, result_on};
+
+    #[test]
+    fn test_form_and_streaks() {
+        let matches = vec![
+            result_on(date(2024, 1, 1), "A", "B", 2, 0),
+            result_on(date(2024, 1, 2), "C", "A", 0, 1),
+            result_on(date(2024, 1, 3), "B", "C", 1, 1),
+            result_on(date(2024, 1, 4), "A", "C", 0, 0),
+            result_on(date(2024, 1, 5), "B", "A", 3, 1),
+            result_on(date(2024, 1, 6), "A", "B", 2, 1),
+        ];
+
+        assert_eq!(form_string("A", &matches), "WWDLW");
+
+        let report = streaks("A", &matches);
+        let winning = report.longest(StreakKind::Winning).unwrap();
+        assert_eq!((winning.length, winning.from.day, winning.to.day), (2, 1, 2));
+        assert_eq!(report.longest(StreakKind::Unbeaten).unwrap().length, 3);
+        assert_eq!(report.longest(StreakKind::Losing).unwrap().length, 1);
+        let scoring = report.longest(StreakKind::Scoring).unwrap();
+        assert_eq!((scoring.length, scoring.from.day), (2, 1));
+
+        assert_eq!(report.current(StreakKind::Winning).unwrap().length, 1);
+        assert_eq!(report.current(StreakKind::Scoring).unwrap().length, 2);
+        assert!(report.current(StreakKind::Losing).is_none());
+    }
+
+    #[test]
+    fn test_form_and_streaks_through_analytics() {
+        let mut analytics = FootballAnalytics::new();
+        for m in [
+            result_on(date(2024, 1, 1), "A", "B", 2, 0),
+            result_on(date(2024, 1, 8), "C", "A", 0, 1),
+            result_on(date(2024, 1, 15), "A", "C", 0, 2),
+            result_on(date(2024, 1, 22), "B", "A", 0, 3),
+            result_on(date(2024, 1, 29), "A", "B", 1, 0),
+        ] {
+            analytics.add_match(m);
+        }
+
+        assert_eq!(analytics.form("A", 10), "WWLWW");
+        assert_eq!(analytics.last_matches("A", 10).len(), 5);
+        assert_eq!(analytics.form("A", 2), "WW");
+
+        // Two winning runs of two; the earlier one is the longest
+        let winning = analytics.streaks("A").longest_winning.unwrap();
+        assert_eq!((winning.from, winning.to), (date(2024, 1, 1), date(2024, 1, 8)));
+        assert_eq!(analytics.streaks("A").current(StreakKind::Winning).unwrap().from, date(2024, 1, 22));
+
+        let january_second_half = DateRange::between(date(2024, 1, 15), date(2024, 1, 31));
+        let report = analytics.streaks_in("A", &january_second_half);
+        assert_eq!(report.longest(StreakKind::Winning).unwrap().from, date(2024, 1, 22));
+        assert_eq!(report.longest(StreakKind::Losing).unwrap().length, 1);
+        assert_eq!(report.longest(StreakKind::Unbeaten).unwrap().length, 2);
+
+        let nobody = analytics.streaks("Z");
+        assert_eq!(analytics.form("Z", 5), "");
+        assert!(analytics.last_matches("Z", 5).is_empty());
+        assert!(StreakKind::ALL.iter().all(|kind| nobody.longest(*kind).is_none()));
+        assert!(nobody.current.is_empty());
+    }
+}
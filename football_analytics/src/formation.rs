@@ -0,0 +1,175 @@
+use std::fmt;
+
+use crate::player::{Player, Position};
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Formation {
+    pub defenders: usize,
+    pub midfielders: usize,
+    pub forwards: usize,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum FormationError {
+    Invalid(String),
+    WrongPlayerCount(usize),    // Outfield players don't add up to 10
+}
+
+// A best XI picked from a squad. Slots the squad can't fill are listed in
+// `gaps` instead of being filled with players out of position.
+#[derive(Debug, Clone)]
+pub struct StartingXi<'a> {
+    pub formation: Formation,
+    pub goalkeeper: Option<&'a Player>,
+    pub defenders: Vec<&'a Player>,
+    pub midfielders: Vec<&'a Player>,
+    pub forwards: Vec<&'a Player>,
+    pub gaps: Vec<(Position, usize)>,
+}
+
+impl Formation {
+    // "4-3-3", "3-5-2", "4-2-3-1": the first line is the defence, the last
+    // the attack and everything in between counts as midfield
+    pub fn parse(s: &str) -> Result<Self, FormationError> {
+        let lines = s
+            .trim()
+            .split('-')
+            .map(|n| n.trim().parse::<usize>())
+            .collect::<Result<Vec<usize>, _>>()
+            .map_err(|_| FormationError::Invalid(s.to_string()))?;
+
+        if lines.len() < 3 || lines.contains(&0) {
+            return Err(FormationError::Invalid(s.to_string()));
+        }
+
+        let outfield: usize = lines.iter().sum();
+        if outfield != 10 {
+            return Err(FormationError::WrongPlayerCount(outfield));
+        }
+
+        Ok(Formation {
+            defenders: lines[0],
+            midfielders: lines[1..lines.len() - 1].iter().sum(),
+            forwards: lines[lines.len() - 1],
+        })
+    }
+
+    pub fn slots(&self, position: &Position) -> usize {
+        match position {
+            Position::Goalkeeper => 1,
+            Position::Defender => self.defenders,
+            Position::Midfielder => self.midfielders,
+            Position::Forward => self.forwards,
+        }
+    }
+}
+
+impl<'a> StartingXi<'a> {
+    pub fn pick(squad: &'a [Player], formation: Formation) -> Self {
+        let mut gaps = Vec::new();
+        let mut best = |position: Position| -> Vec<&'a Player> {
+            let slots = formation.slots(&position);
+            let mut candidates: Vec<&Player> = squad.iter().filter(|p| p.position == position).collect();
+            candidates.sort_by(|a, b| b.rating.cmp(&a.rating).then_with(|| a.jersey_number.cmp(&b.jersey_number)));
+            candidates.truncate(slots);
+            if candidates.len() < slots {
+                gaps.push((position, slots - candidates.len()));
+            }
+            candidates
+        };
+
+        let goalkeeper = best(Position::Goalkeeper).into_iter().next();
+        let defenders = best(Position::Defender);
+        let midfielders = best(Position::Midfielder);
+        let forwards = best(Position::Forward);
+
+        StartingXi {
+            formation,
+            goalkeeper,
+            defenders,
+            midfielders,
+            forwards,
+            gaps,
+        }
+    }
+
+    pub fn players(&self) -> Vec<&'a Player> {
+        self.goalkeeper
+            .into_iter()
+            .chain(self.defenders.iter().copied())
+            .chain(self.midfielders.iter().copied())
+            .chain(self.forwards.iter().copied())
+            .collect()
+    }
+
+    pub fn is_complete(&self) -> bool {
+        self.gaps.is_empty()
+    }
+
+    // Sum of the selected players' ratings; empty slots add nothing, so an
+    // incomplete lineup always compares badly
+    pub fn total_rating(&self) -> u32 {
+        self.players().iter().map(|p| p.rating as u32).sum()
+    }
+
+    pub fn average_rating(&self) -> f64 {
+        let players = self.players();
+        if players.is_empty() {
+            0.0
+        } else {
+            self.total_rating() as f64 / players.len() as f64
+        }
+    }
+}
+
+impl fmt::Display for Formation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}-{}-{}", self.defenders, self.midfielders, self.forwards)
+    }
+}
+
+impl fmt::Display for FormationError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            FormationError::Invalid(s) => write!(f, "'{}' is not a formation like 4-3-3", s),
+            FormationError::WrongPlayerCount(n) => write!(f, "formation has {} outfield players instead of 10", n),
+        }
+    }
+}
+
+impl std::error::Error for FormationError {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::player;
+
+    #[test]
+    fn test_parse_formations() {
+        assert_eq!(
+            Formation::parse("4-2-3-1"),
+            Ok(Formation { defenders: 4, midfielders: 5, forwards: 1 })
+        );
+        assert_eq!(Formation::parse("4-4-3"), Err(FormationError::WrongPlayerCount(11)));
+        assert!(matches!(Formation::parse("4-six"), Err(FormationError::Invalid(_))));
+        assert!(matches!(Formation::parse("10"), Err(FormationError::Invalid(_))));
+    }
+
+    #[test]
+    fn test_pick_takes_best_rated_and_reports_gaps() {
+        let squad = vec![
+            player("Keeper", 1, Position::Goalkeeper, 80),
+            player("Good Defender", 2, Position::Defender, 85),
+            player("Bad Defender", 3, Position::Defender, 60),
+            player("Mid", 8, Position::Midfielder, 75),
+            player("Striker", 9, Position::Forward, 90),
+        ];
+
+        let xi = StartingXi::pick(&squad, Formation::parse("1-4-5").unwrap());
+        assert_eq!(xi.defenders.len(), 1);
+        assert_eq!(xi.defenders[0].name, "Good Defender");
+        assert_eq!(xi.gaps, vec![(Position::Midfielder, 3), (Position::Forward, 4)]);
+        assert!(!xi.is_complete());
+        assert_eq!(xi.total_rating(), 80 + 85 + 75 + 90);
+    }
+}
@@ -0,0 +1,200 @@
+use crate::match_data::MatchData;
+use crate::record::Record;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum MeetingResult {
+    Team1Win,
+    Team2Win,
+    Draw,
+}
+
+// Every meeting between two teams, seen from `team1`'s side
+#[derive(Debug, Clone)]
+pub struct HeadToHead {
+    pub team1: String,
+    pub team2: String,
+    pub overall: Record,
+    pub team1_at_home: Record,      // Meetings hosted by team1
+    pub team1_away: Record,         // Meetings hosted by team2
+    pub biggest_team1_win: Option<MatchData>,
+    pub biggest_team2_win: Option<MatchData>,
+    pub meetings: Vec<MatchData>,   // Oldest first
+    pub current_streak: (MeetingResult, usize),
+}
+
+impl HeadToHead {
+    // None if the two teams never met in `matches`
+    pub fn from_matches<'a, I>(team1: &str, team2: &str, matches: I) -> Option<Self>
+    where
+        I: IntoIterator<Item = &'a MatchData>,
+    {
+        let meetings: Vec<MatchData> = matches
+            .into_iter()
+            .filter(|m| {
+                (m.home_team == team1 && m.away_team == team2) || (m.home_team == team2 && m.away_team == team1)
+            })
+            .cloned()
+            .collect();
+
+        if meetings.is_empty() {
+            return None;
+        }
+
+        let mut overall = Record::default();
+        let mut team1_at_home = Record::default();
+        let mut team1_away = Record::default();
+        let mut biggest_team1_win: Option<&MatchData> = None;
+        let mut biggest_team2_win: Option<&MatchData> = None;
+        let mut current_streak = (MeetingResult::Draw, 0);
+
+        for m in &meetings {
+            let (scored, conceded) = if m.home_team == team1 {
+                (m.home_score, m.away_score)
+            } else {
+                (m.away_score, m.home_score)
+            };
+
+            overall.add(scored, conceded);
+            if m.home_team == team1 {
+                team1_at_home.add(scored, conceded);
+            } else {
+                team1_away.add(scored, conceded);
+            }
+
+            let result = if scored > conceded {
+                if is_bigger_win(m, biggest_team1_win) {
+                    biggest_team1_win = Some(m);
+                }
+                MeetingResult::Team1Win
+            } else if scored < conceded {
+                if is_bigger_win(m, biggest_team2_win) {
+                    biggest_team2_win = Some(m);
+                }
+                MeetingResult::Team2Win
+            } else {
+                MeetingResult::Draw
+            };
+
+            if current_streak.0 == result {
+                current_streak.1 += 1;
+            } else {
+                current_streak = (result, 1);
+            }
+        }
+
+        let biggest_team1_win = biggest_team1_win.cloned();
+        let biggest_team2_win = biggest_team2_win.cloned();
+
+        Some(HeadToHead {
+            team1: team1.to_string(),
+            team2: team2.to_string(),
+            overall,
+            team1_at_home,
+            team1_away,
+            biggest_team1_win,
+            biggest_team2_win,
+            meetings,
+            current_streak,
+        })
+    }
+
+    pub fn team1_wins(&self) -> usize {
+        self.overall.won
+    }
+
+    pub fn team2_wins(&self) -> usize {
+        self.overall.lost
+    }
+
+    pub fn draws(&self) -> usize {
+        self.overall.drawn
+    }
+
+    pub fn last_meeting(&self) -> Option<&MatchData> {
+        self.meetings.last()
+    }
+
+    // "FC Barcelona won the last 2", "last 3 drawn"
+    pub fn streak_summary(&self) -> String {
+        let (result, length) = self.current_streak;
+        match result {
+            MeetingResult::Team1Win => format!("{} won the last {}", self.team1, length),
+            MeetingResult::Team2Win => format!("{} won the last {}", self.team2, length),
+            MeetingResult::Draw => format!("last {} drawn", length),
+        }
+    }
+}
+
+// Wider margin wins; on equal margins the higher-scoring game does
+fn is_bigger_win(m: &MatchData, current: Option<&MatchData>) -> bool {
+    match current {
+        None => true,
+        Some(best) => {
+            let margin = m.home_score.abs_diff(m.away_score);
+            let best_margin = best.home_score.abs_diff(best.away_score);
+            margin > best_margin || (margin == best_margin && m.total_goals() > best.total_goals())
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::{date, result_on};
+
+    fn meetings() -> Vec<MatchData> {
+        vec![
+            result_on(date(2023, 8, 1), "A", "B", 3, 0),
+            result_on(date(2023, 9, 1), "B", "A", 2, 2),
+            result_on(date(2023, 10, 1), "A", "C", 5, 0),
+            result_on(date(2023, 11, 1), "B", "A", 4, 1),
+            result_on(date(2023, 12, 1), "A", "B", 1, 2),
+        ]
+    }
+
+    #[test]
+    fn test_never_met() {
+        assert!(HeadToHead::from_matches("B", "C", &meetings()).is_none());
+    }
+
+    #[test]
+    fn test_record_from_both_sides() {
+        let matches = meetings();
+        let a = HeadToHead::from_matches("A", "B", &matches).unwrap();
+        let b = HeadToHead::from_matches("B", "A", &matches).unwrap();
+
+        assert_eq!(a.meetings.len(), 4);
+        assert_eq!((a.team1_wins(), a.draws(), a.team2_wins()), (1, 1, 2));
+        assert_eq!((b.team1_wins(), b.draws(), b.team2_wins()), (2, 1, 1));
+        assert_eq!((a.overall.goals_for, a.overall.goals_against), (7, 8));
+        assert_eq!((b.overall.goals_for, b.overall.goals_against), (8, 7));
+
+        // A hosted the 3-0 and the 1-2, B the 2-2 and the 4-1
+        assert_eq!((a.team1_at_home.won, a.team1_at_home.lost), (1, 1));
+        assert_eq!((a.team1_away.drawn, a.team1_away.lost), (1, 1));
+        assert_eq!(b.team1_at_home.goals_for, 6);
+    }
+
+    #[test]
+    fn test_streak_and_biggest_wins() {
+        let matches = meetings();
+        let a = HeadToHead::from_matches("A", "B", &matches).unwrap();
+
+        assert_eq!(a.current_streak, (MeetingResult::Team2Win, 2));
+        assert_eq!(a.streak_summary(), "B won the last 2");
+        assert_eq!(a.biggest_team1_win.as_ref().unwrap().date, date(2023, 8, 1));
+        // 4-1 and 1-2: the wider margin wins
+        assert_eq!(a.biggest_team2_win.as_ref().unwrap().date, date(2023, 11, 1));
+        assert_eq!(a.last_meeting().unwrap().date, date(2023, 12, 1));
+    }
+
+    #[test]
+    fn test_equal_margins_prefer_more_goals_without_overflow() {
+        let matches = vec![
+            result_on(date(2024, 1, 1), "A", "B", 150, 50),
+            result_on(date(2024, 2, 1), "A", "B", 200, 100),
+        ];
+        let h2h = HeadToHead::from_matches("A", "B", &matches).unwrap();
+        assert_eq!(h2h.biggest_team1_win.unwrap().home_score, 200);
+    }
+}
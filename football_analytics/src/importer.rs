@@ -0,0 +1,370 @@
+use std::fmt;
+use std::fs::File;
+use std::io::{BufRead, BufReader};
+use std::path::Path;
+
+use crate::analytics::FootballAnalytics;
+use crate::competition::Scope;
+use crate::date::{KickoffTime, MatchDate};
+use crate::events::Goal;
+use crate::match_data::MatchData;
+
+// Reads match results from football-data.co.uk style CSV files.
+//
+// Required columns: Date, HomeTeam, AwayTeam, FTHG, FTAG (header names are
+// case-insensitive, "Home"/"Away" are accepted too). Optional columns:
+// Time (kickoff, "15:00"), and HomeScorers/AwayScorers holding the goals
+// for each side separated by ';' in the format read by `Goal::parse`,
+// e.g. "Messi 23' (Alba); Pique 45+1'". Any other column (Div, FTR,
+// odds...) is ignored.
+//
+// The files carry no competition names, so the caller tags every row with
+// the competition and season of `tag`; `Scope::all()` leaves them untagged.
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum OnBadRow {
+    Skip,   // Record the problem and keep going
+    Fail,   // Stop at the first bad row, nothing gets imported
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct RowError {
+    pub line: usize,            // 1-based line number in the file
+    pub message: String,
+}
+
+#[derive(Debug)]
+pub enum ImportError {
+    Io(std::io::Error),
+    EmptyFile,
+    MissingColumn(&'static str),
+    BadRow(RowError),
+}
+
+#[derive(Debug, Default)]
+pub struct ImportReport {
+    pub imported: usize,
+    pub skipped: Vec<RowError>,
+}
+
+impl fmt::Display for RowError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "line {}: {}", self.line, self.message)
+    }
+}
+
+impl fmt::Display for ImportError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ImportError::Io(e) => write!(f, "could not read CSV: {}", e),
+            ImportError::EmptyFile => write!(f, "CSV file has no header row"),
+            ImportError::MissingColumn(name) => write!(f, "missing required column '{}'", name),
+            ImportError::BadRow(row) => write!(f, "{}", row),
+        }
+    }
+}
+
+impl std::error::Error for ImportError {}
+
+impl From<std::io::Error> for ImportError {
+    fn from(e: std::io::Error) -> Self {
+        ImportError::Io(e)
+    }
+}
+
+// Column positions resolved from the header row
+struct Columns {
+    date: usize,
+    time: Option<usize>,
+    home_team: usize,
+    away_team: usize,
+    home_goals: usize,
+    away_goals: usize,
+    home_scorers: Option<usize>,
+    away_scorers: Option<usize>,
+}
+
+impl Columns {
+    fn from_header(header: &[String]) -> Result<Self, ImportError> {
+        let find = |names: &[&str]| {
+            header.iter().position(|h| names.iter().any(|n| h.eq_ignore_ascii_case(n)))
+        };
+
+        Ok(Columns {
+            date: find(&["Date"]).ok_or(ImportError::MissingColumn("Date"))?,
+            time: find(&["Time"]),
+            home_team: find(&["HomeTeam", "Home"]).ok_or(ImportError::MissingColumn("HomeTeam"))?,
+            away_team: find(&["AwayTeam", "Away"]).ok_or(ImportError::MissingColumn("AwayTeam"))?,
+            home_goals: find(&["FTHG", "HG"]).ok_or(ImportError::MissingColumn("FTHG"))?,
+            away_goals: find(&["FTAG", "AG"]).ok_or(ImportError::MissingColumn("FTAG"))?,
+            home_scorers: find(&["HomeScorers"]),
+            away_scorers: find(&["AwayScorers"]),
+        })
+    }
+}
+
+pub fn import_csv_file<P: AsRef<Path>>(
+    path: P,
+    analytics: &mut FootballAnalytics,
+    on_bad_row: OnBadRow,
+    tag: &Scope,
+) -> Result<ImportReport, ImportError> {
+    let file = File::open(path)?;
+    import_csv(BufReader::new(file), analytics, on_bad_row, tag)
+}
+
+pub fn import_csv<R: BufRead>(
+    reader: R,
+    analytics: &mut FootballAnalytics,
+    on_bad_row: OnBadRow,
+    tag: &Scope,
+) -> Result<ImportReport, ImportError> {
+    let mut lines = reader.lines().enumerate();
+
+    let header = loop {
+        match lines.next() {
+            Some((_, line)) => {
+                let line = line?;
+                if !line.trim().is_empty() {
+                    break split_csv_line(line.trim_start_matches('\u{feff}'));
+                }
+            }
+            None => return Err(ImportError::EmptyFile),
+        }
+    };
+    let columns = Columns::from_header(&header)?;
+
+    // Collect everything first so a failed import leaves `analytics` untouched
+    let mut parsed = Vec::new();
+    let mut report = ImportReport::default();
+
+    for (index, line) in lines {
+        let line = line?;
+        if line.trim().is_empty() {
+            continue;
+        }
+
+        let fields = split_csv_line(&line);
+        match parse_row(&fields, &columns) {
+            Ok(mut match_data) => {
+                match_data.competition = tag.competition.clone();
+                match_data.season = tag.season;
+                parsed.push(match_data);
+            }
+            Err(message) => {
+                let error = RowError { line: index + 1, message };
+                match on_bad_row {
+                    OnBadRow::Skip => report.skipped.push(error),
+                    OnBadRow::Fail => return Err(ImportError::BadRow(error)),
+                }
+            }
+        }
+    }
+
+    report.imported = parsed.len();
+    for match_data in parsed {
+        analytics.add_match(match_data);
+    }
+
+    Ok(report)
+}
+
+fn parse_row(fields: &[String], columns: &Columns) -> Result<MatchData, String> {
+    let field = |index: usize, name: &str| -> Result<&str, String> {
+        match fields.get(index).map(|f| f.trim()) {
+            Some(value) if !value.is_empty() => Ok(value),
+            _ => Err(format!("missing value for {}", name)),
+        }
+    };
+    let goals = |index: usize, name: &str| -> Result<u8, String> {
+        let value = field(index, name)?;
+        value
+            .parse::<u8>()
+            .map_err(|_| format!("{} '{}' is not a valid score", name, value))
+    };
+
+    let date_str = field(columns.date, "Date")?;
+    let date = MatchDate::parse(date_str).ok_or_else(|| format!("Date '{}' is not a valid date", date_str))?;
+
+    let kickoff = match columns.time.and_then(|c| fields.get(c)).map(|t| t.trim()) {
+        Some(time) if !time.is_empty() => {
+            Some(KickoffTime::parse(time).ok_or_else(|| format!("Time '{}' is not a valid kickoff time", time))?)
+        }
+        _ => None,
+    };
+
+    let home_team = field(columns.home_team, "HomeTeam")?.to_string();
+    let away_team = field(columns.away_team, "AwayTeam")?.to_string();
+
+    let home_score = goals(columns.home_goals, "FTHG")?;
+    let away_score = goals(columns.away_goals, "FTAG")?;
+
+    let home_goals = parse_goals(fields, columns.home_scorers, "HomeScorers")?;
+    let away_goals = parse_goals(fields, columns.away_scorers, "AwayScorers")?;
+
+    let mut match_data = MatchData::new(date, home_team, away_team, home_score, away_score, home_goals, away_goals);
+    match_data.kickoff = kickoff;
+    match_data.validate()?;
+    Ok(match_data)
+}
+
+// An empty scorer cell is fine (many sources don't track scorers), but a
+// filled one has to agree with the final score; `MatchData::validate`
+// checks that.
+fn parse_goals(fields: &[String], column: Option<usize>, name: &str) -> Result<Vec<Goal>, String> {
+    let cell = match column.and_then(|c| fields.get(c)) {
+        Some(cell) if !cell.trim().is_empty() => cell,
+        _ => return Ok(Vec::new()),
+    };
+
+    cell.split(';')
+        .map(|s| s.trim())
+        .filter(|s| !s.is_empty())
+        .map(|s| Goal::parse(s).ok_or_else(|| format!("{} entry '{}' is not a valid goal", name, s)))
+        .collect()
+}
+
+// Splits one CSV record, honouring double-quoted fields and "" escapes
+fn split_csv_line(line: &str) -> Vec<String> {
+    let mut fields = Vec::new();
+    let mut current = String::new();
+    let mut in_quotes = false;
+    let mut chars = line.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        match c {
+            '"' if in_quotes && chars.peek() == Some(&'"') => {
+                current.push('"');
+                chars.next();
+            }
+            '"' => in_quotes = !in_quotes,
+            ',' if !in_quotes => fields.push(std::mem::take(&mut current)),
+            _ => current.push(c),
+        }
+    }
+    fields.push(current);
+
+    fields
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::events::GoalKind;
+    use crate::test_util::date;
+
+    const HEADER: &str = "Div,Date,HomeTeam,AwayTeam,FTHG,FTAG,FTR,HomeScorers,AwayScorers";
+
+    fn import(csv: &str, on_bad_row: OnBadRow) -> (FootballAnalytics, Result<ImportReport, ImportError>) {
+        let mut analytics = FootballAnalytics::new();
+        let result = import_csv(csv.as_bytes(), &mut analytics, on_bad_row, &Scope::all());
+        (analytics, result)
+    }
+
+    #[test]
+    fn test_imports_valid_rows() {
+        let csv = format!(
+            "{}\nSP1,13/08/2023,Barcelona,Real Madrid,2,1,H,Messi 23' (Alba);Ramos 45+1' (og),Benzema 60' (pen)\nSP1,20/08/2023,Sevilla,Valencia,0,0,D,,\n",
+            HEADER
+        );
+        let (analytics, result) = import(&csv, OnBadRow::Fail);
+        let report = result.unwrap();
+
+        assert_eq!(report.imported, 2);
+        assert!(report.skipped.is_empty());
+        assert_eq!(analytics.matches().len(), 2);
+        let first = &analytics.matches()[0];
+        assert_eq!(first.get_all_scorers(), vec!["Messi", "Benzema"]);
+        assert_eq!(first.home_goals[0].assist.as_deref(), Some("Alba"));
+        assert_eq!(first.home_goals[1].kind, GoalKind::OwnGoal);
+        assert_eq!(first.home_goals[1].added_time, 1);
+    }
+
+    #[test]
+    fn test_scorers_without_minutes() {
+        let csv = format!("{}\nSP1,13/08/2023,Barcelona,Real Madrid,2,0,H,Messi;Lionel Messi (pen),\n", HEADER);
+        let (analytics, result) = import(&csv, OnBadRow::Fail);
+
+        assert_eq!(result.unwrap().imported, 1);
+        assert_eq!(analytics.matches()[0].get_all_scorers(), vec!["Messi", "Lionel Messi"]);
+    }
+
+    #[test]
+    fn test_rows_are_tagged_with_the_scope() {
+        let csv = format!("{}\nSP1,13/08/2023,Barcelona,Real Madrid,1,0,H,,\n", HEADER);
+        let mut analytics = FootballAnalytics::new();
+        import_csv(csv.as_bytes(), &mut analytics, OnBadRow::Fail, &Scope::competition("La Liga")).unwrap();
+
+        assert_eq!(analytics.matches()[0].competition.as_deref(), Some("La Liga"));
+        assert_eq!(analytics.matches()[0].season, None);
+        assert_eq!(analytics.matches_for(&Scope::competition("La Liga")).len(), 1);
+    }
+
+    #[test]
+    fn test_skip_reports_line_numbers() {
+        let csv = format!(
+            "{}\nSP1,13/08/2023,Barcelona,Real Madrid,two,1,H,,\n\nSP1,20/08/2023,Sevilla,Valencia,1,0,H,,\n",
+            HEADER
+        );
+        let (analytics, result) = import(&csv, OnBadRow::Skip);
+        let report = result.unwrap();
+
+        assert_eq!(report.imported, 1);
+        assert_eq!(report.skipped.len(), 1);
+        assert_eq!(report.skipped[0].line, 2);
+        assert_eq!(analytics.matches().len(), 1);
+    }
+
+    #[test]
+    fn test_fail_leaves_analytics_untouched() {
+        let csv = format!(
+            "{}\nSP1,13/08/2023,Barcelona,Real Madrid,1,0,H,,\nSP1,20/08/2023,Sevilla,Valencia,1,0,H,,Someone 10'\n",
+            HEADER
+        );
+        let (analytics, result) = import(&csv, OnBadRow::Fail);
+
+        match result {
+            Err(ImportError::BadRow(row)) => assert_eq!(row.line, 3),
+            other => panic!("expected a bad row error, got {:?}", other),
+        }
+        assert!(analytics.matches().is_empty());
+    }
+
+    #[test]
+    fn test_bad_date_is_a_bad_row() {
+        let csv = format!(
+            "{}\nSP1,31/02/2023,Barcelona,Real Madrid,1,0,H,,\nSP1,01/01/0000,Barcelona,Real Madrid,1,0,H,,\n",
+            HEADER
+        );
+        let (_, result) = import(&csv, OnBadRow::Skip);
+        let report = result.unwrap();
+
+        assert_eq!(report.imported, 0);
+        assert!(report.skipped[0].message.contains("31/02/2023"));
+        assert!(report.skipped[1].message.contains("01/01/0000"));
+    }
+
+    #[test]
+    fn test_rows_are_sorted_by_date() {
+        let csv = format!(
+            "{}\nSP1,20/08/23,Sevilla,Valencia,1,0,H,,\nSP1,13/08/23,Barcelona,Real Madrid,1,0,H,,\n",
+            HEADER
+        );
+        let (analytics, _) = import(&csv, OnBadRow::Fail);
+
+        assert_eq!(analytics.matches()[0].home_team, "Barcelona");
+        assert_eq!(analytics.matches()[0].date, date(2023, 8, 13));
+    }
+
+    #[test]
+    fn test_missing_column() {
+        let (_, result) = import("Date,HomeTeam,AwayTeam,FTHG\n", OnBadRow::Skip);
+        assert!(matches!(result, Err(ImportError::MissingColumn("FTAG"))));
+    }
+
+    #[test]
+    fn test_quoted_fields() {
+        let fields = split_csv_line(r#"a,"Brighton, Hove","say ""hi""",b"#);
+        assert_eq!(fields, vec!["a", "Brighton, Hove", "say \"hi\"", "b"]);
+    }
+}
@@ -0,0 +1,454 @@
+use std::fmt;
+
+use rand::rngs::StdRng;
+use rand::seq::SliceRandom;
+use rand::SeedableRng;
+
+use crate::match_data::MatchData;
+
+// Cup competitions. `MatchData` stays a 90-minute result; extra time and
+// penalties live on the `Leg` that needed them.
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum TieFormat {
+    SingleLeg,
+    TwoLegged { away_goals: bool },     // Whether away goals break a level aggregate
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Decision {
+    Score,          // Won in normal time (on aggregate for two legs)
+    AwayGoals,
+    ExtraTime,
+    Penalties,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Leg {
+    pub match_data: MatchData,
+    pub extra_time: Option<(u8, u8)>,   // Goals scored in extra time only (home, away)
+    pub penalties: Option<(u8, u8)>,    // Shootout score (home, away)
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Tie {
+    pub team1: String,                  // Home in a single leg, hosts the first leg otherwise
+    pub team2: String,
+    pub format: TieFormat,
+    pub legs: Vec<Leg>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Round {
+    pub name: String,
+    pub ties: Vec<Tie>,
+}
+
+#[derive(Debug, Clone)]
+pub struct Bracket {
+    pub format: TieFormat,
+    pub final_format: TieFormat,
+    rounds: Vec<Round>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum KnockoutError {
+    BadTeamCount(usize),                // Brackets need 2, 4, 8, 16... teams
+    TieNotFound(String, String),
+    TieAlreadyDecided,
+    WrongVenue { expected_home: String },
+    UnneededExtraTime,                  // Extra time or penalties after a decided tie
+    Undecided,                          // Deciding leg level without extra time/penalties
+}
+
+impl fmt::Display for KnockoutError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            KnockoutError::BadTeamCount(n) => write!(f, "a bracket needs a power of two teams, got {}", n),
+            KnockoutError::TieNotFound(a, b) => write!(f, "no open tie between {} and {}", a, b),
+            KnockoutError::TieAlreadyDecided => write!(f, "tie is already decided"),
+            KnockoutError::WrongVenue { expected_home } => write!(f, "this leg should be hosted by {}", expected_home),
+            KnockoutError::UnneededExtraTime => write!(f, "extra time or penalties recorded for a decided tie"),
+            KnockoutError::Undecided => write!(f, "deciding leg is level: extra time and penalties are required"),
+        }
+    }
+}
+
+impl std::error::Error for KnockoutError {}
+
+impl fmt::Display for Decision {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Decision::Score => write!(f, "in normal time"),
+            Decision::AwayGoals => write!(f, "on away goals"),
+            Decision::ExtraTime => write!(f, "after extra time"),
+            Decision::Penalties => write!(f, "on penalties"),
+        }
+    }
+}
+
+impl Leg {
+    pub fn new(match_data: MatchData) -> Self {
+        Leg {
+            match_data,
+            extra_time: None,
+            penalties: None,
+        }
+    }
+
+    pub fn with_extra_time(mut self, home_goals: u8, away_goals: u8) -> Self {
+        self.extra_time = Some((home_goals, away_goals));
+        self
+    }
+
+    pub fn with_penalties(mut self, home: u8, away: u8) -> Self {
+        self.penalties = Some((home, away));
+        self
+    }
+}
+
+impl Tie {
+    pub fn new(team1: String, team2: String, format: TieFormat) -> Self {
+        Tie {
+            team1,
+            team2,
+            format,
+            legs: Vec::new(),
+        }
+    }
+
+    pub fn legs_needed(&self) -> usize {
+        match self.format {
+            TieFormat::SingleLeg => 1,
+            TieFormat::TwoLegged { .. } => 2,
+        }
+    }
+
+    pub fn involves(&self, a: &str, b: &str) -> bool {
+        (self.team1 == a && self.team2 == b) || (self.team1 == b && self.team2 == a)
+    }
+
+    // Validates the leg against the tie so far before storing it
+    pub fn add_leg(&mut self, leg: Leg) -> Result<(), KnockoutError> {
+        if self.legs.len() >= self.legs_needed() {
+            return Err(KnockoutError::TieAlreadyDecided);
+        }
+
+        let expected_home = if self.legs.is_empty() { &self.team1 } else { &self.team2 };
+        if &leg.match_data.home_team != expected_home {
+            return Err(KnockoutError::WrongVenue { expected_home: expected_home.clone() });
+        }
+
+        let deciding = self.legs.len() + 1 == self.legs_needed();
+        let has_extras = leg.extra_time.is_some() || leg.penalties.is_some();
+        if !deciding && has_extras {
+            return Err(KnockoutError::UnneededExtraTime);
+        }
+
+        self.legs.push(leg);
+        if deciding {
+            if let Err(e) = self.resolve().map(|_| ()) {
+                self.legs.pop();
+                return Err(e);
+            }
+        }
+        Ok(())
+    }
+
+    pub fn is_complete(&self) -> bool {
+        self.legs.len() == self.legs_needed()
+    }
+
+    // Aggregate (team1, team2) including extra time
+    pub fn aggregate(&self) -> (u32, u32) {
+        self.totals(true)
+    }
+
+    fn totals(&self, include_extra: bool) -> (u32, u32) {
+        self.legs.iter().fold((0, 0), |(t1, t2), leg| {
+            let m = &leg.match_data;
+            let (extra_home, extra_away) = if include_extra { leg.extra_time.unwrap_or((0, 0)) } else { (0, 0) };
+            let home = m.home_score as u32 + extra_home as u32;
+            let away = m.away_score as u32 + extra_away as u32;
+            if m.home_team == self.team1 { (t1 + home, t2 + away) } else { (t1 + away, t2 + home) }
+        })
+    }
+
+    pub fn winner(&self) -> Option<&str> {
+        if !self.is_complete() {
+            return None;
+        }
+        self.resolve().ok().map(|(winner, _)| winner)
+    }
+
+    pub fn decided_by(&self) -> Option<Decision> {
+        if !self.is_complete() {
+            return None;
+        }
+        self.resolve().ok().map(|(_, decision)| decision)
+    }
+
+    // Works out the winner of a complete tie, checking that extra time and
+    // penalties were used exactly when the rules called for them
+    fn resolve(&self) -> Result<(&str, Decision), KnockoutError> {
+        let deciding = self.legs.last().ok_or(KnockoutError::Undecided)?;
+        let m = &deciding.match_data;
+        let team1_hosts_decider = m.home_team == self.team1;
+
+        let pick = |team1_wins: bool| if team1_wins { self.team1.as_str() } else { self.team2.as_str() };
+
+        // After 90 minutes
+        let (t1, t2) = self.totals(false);
+        if t1 != t2 {
+            if deciding.extra_time.is_some() || deciding.penalties.is_some() {
+                return Err(KnockoutError::UnneededExtraTime);
+            }
+            return Ok((pick(t1 > t2), Decision::Score));
+        }
+        if let Some(team1_wins) = self.away_goals_winner(false) {
+            if deciding.extra_time.is_some() || deciding.penalties.is_some() {
+                return Err(KnockoutError::UnneededExtraTime);
+            }
+            return Ok((pick(team1_wins), Decision::AwayGoals));
+        }
+
+        // After extra time
+        if deciding.extra_time.is_none() {
+            return Err(KnockoutError::Undecided);
+        }
+        let (t1, t2) = self.totals(true);
+        if t1 != t2 {
+            if deciding.penalties.is_some() {
+                return Err(KnockoutError::UnneededExtraTime);
+            }
+            return Ok((pick(t1 > t2), Decision::ExtraTime));
+        }
+        if let Some(team1_wins) = self.away_goals_winner(true) {
+            if deciding.penalties.is_some() {
+                return Err(KnockoutError::UnneededExtraTime);
+            }
+            return Ok((pick(team1_wins), Decision::AwayGoals));
+        }
+
+        // Penalties
+        match deciding.penalties {
+            Some((home, away)) if home != away => {
+                let decider_home_wins = home > away;
+                Ok((pick(decider_home_wins == team1_hosts_decider), Decision::Penalties))
+            }
+            _ => Err(KnockoutError::Undecided),
+        }
+    }
+
+    // Some(true) if team1 goes through on away goals, None if the rule
+    // doesn't apply or away goals are level too
+    fn away_goals_winner(&self, include_extra: bool) -> Option<bool> {
+        if self.format != (TieFormat::TwoLegged { away_goals: true }) {
+            return None;
+        }
+
+        let mut team1_away = 0;
+        let mut team2_away = 0;
+        for leg in &self.legs {
+            let m = &leg.match_data;
+            let (_, extra_away) = if include_extra { leg.extra_time.unwrap_or((0, 0)) } else { (0, 0) };
+            let away_goals = m.away_score as u32 + extra_away as u32;
+            if m.away_team == self.team1 {
+                team1_away += away_goals;
+            } else {
+                team2_away += away_goals;
+            }
+        }
+
+        if team1_away == team2_away {
+            None
+        } else {
+            Some(team1_away > team2_away)
+        }
+    }
+}
+
+impl Bracket {
+    // Teams in seed order (best first). Seeds are placed so that 1 and 2
+    // can only meet in the final, 1-4 in the semi-finals, and so on.
+    pub fn seeded_draw(teams: &[String], format: TieFormat, final_format: TieFormat) -> Result<Self, KnockoutError> {
+        if teams.len() < 2 || !teams.len().is_power_of_two() {
+            return Err(KnockoutError::BadTeamCount(teams.len()));
+        }
+
+        let order = seed_positions(teams.len());
+        let placed: Vec<String> = order.iter().map(|seed| teams[seed - 1].clone()).collect();
+        Ok(Bracket::from_draw(placed, format, final_format))
+    }
+
+    // Each seeded team is drawn against a random unseeded one
+    pub fn pot_draw(
+        seeded: &[String],
+        unseeded: &[String],
+        format: TieFormat,
+        final_format: TieFormat,
+        seed: u64,
+    ) -> Result<Self, KnockoutError> {
+        let total = seeded.len() + unseeded.len();
+        if seeded.len() != unseeded.len() || total < 2 || !total.is_power_of_two() {
+            return Err(KnockoutError::BadTeamCount(total));
+        }
+
+        let mut rng = StdRng::seed_from_u64(seed);
+        let mut seeded = seeded.to_vec();
+        let mut unseeded = unseeded.to_vec();
+        seeded.shuffle(&mut rng);
+        unseeded.shuffle(&mut rng);
+
+        // Unseeded teams host the first leg
+        let placed = unseeded.into_iter().zip(seeded).flat_map(|(u, s)| [u, s]).collect();
+        Ok(Bracket::from_draw(placed, format, final_format))
+    }
+
+    fn from_draw(placed: Vec<String>, format: TieFormat, final_format: TieFormat) -> Self {
+        let round_format = if placed.len() == 2 { final_format } else { format };
+        let ties = placed
+            .chunks(2)
+            .map(|pair| Tie::new(pair[0].clone(), pair[1].clone(), round_format))
+            .collect();
+
+        Bracket {
+            format,
+            final_format,
+            rounds: vec![Round { name: round_name(placed.len()), ties }],
+        }
+    }
+
+    pub fn rounds(&self) -> &[Round] {
+        &self.rounds
+    }
+
+    pub fn current_round(&self) -> &Round {
+        self.rounds.last().expect("a bracket always has a round")
+    }
+
+    // Records a leg in the current round. Once every tie in the round is
+    // decided the winners are paired off into the next round.
+    pub fn record_leg(&mut self, leg: Leg) -> Result<(), KnockoutError> {
+        let home = leg.match_data.home_team.clone();
+        let away = leg.match_data.away_team.clone();
+
+        let round = self.rounds.last_mut().expect("a bracket always has a round");
+        let tie = round
+            .ties
+            .iter_mut()
+            .find(|t| t.involves(&home, &away))
+            .ok_or(KnockoutError::TieNotFound(home, away))?;
+        tie.add_leg(leg)?;
+
+        self.advance();
+        Ok(())
+    }
+
+    pub fn champion(&self) -> Option<&str> {
+        let round = self.current_round();
+        if round.ties.len() == 1 {
+            round.ties[0].winner()
+        } else {
+            None
+        }
+    }
+
+    fn advance(&mut self) {
+        let round = self.current_round();
+        if round.ties.len() < 2 {
+            return;
+        }
+
+        let winners: Option<Vec<String>> = round.ties.iter().map(|t| t.winner().map(|w| w.to_string())).collect();
+        if let Some(winners) = winners {
+            let format = if winners.len() == 2 { self.final_format } else { self.format };
+            let ties = winners
+                .chunks(2)
+                .map(|pair| Tie::new(pair[0].clone(), pair[1].clone(), format))
+                .collect();
+            self.rounds.push(Round { name: round_name(winners.len()), ties });
+        }
+    }
+}
+
+// Bracket slots for seeds 1..=n, e.g. n = 8 gives 1 8 4 5 2 7 3 6
+fn seed_positions(n: usize) -> Vec<usize> {
+    let mut positions = vec![1];
+    while positions.len() < n {
+        let size = positions.len() * 2;
+        positions = positions.iter().flat_map(|&s| [s, size + 1 - s]).collect();
+    }
+    positions
+}
+
+fn round_name(teams: usize) -> String {
+    match teams {
+        2 => "Final".to_string(),
+        4 => "Semi-finals".to_string(),
+        8 => "Quarter-finals".to_string(),
+        n => format!("Round of {}", n),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::result;
+
+    fn two_legged(away_goals: bool) -> Tie {
+        Tie::new("A".to_string(), "B".to_string(), TieFormat::TwoLegged { away_goals })
+    }
+
+    #[test]
+    fn test_away_goals_rule() {
+        let mut tie = two_legged(true);
+        tie.add_leg(Leg::new(result("A", "B", 1, 2))).unwrap();
+        tie.add_leg(Leg::new(result("B", "A", 0, 1))).unwrap();
+
+        assert_eq!(tie.aggregate(), (2, 2));
+        assert_eq!(tie.winner(), Some("B"));
+        assert_eq!(tie.decided_by(), Some(Decision::AwayGoals));
+    }
+
+    #[test]
+    fn test_level_tie_needs_extra_time_then_penalties() {
+        let mut tie = two_legged(false);
+        tie.add_leg(Leg::new(result("A", "B", 1, 2))).unwrap();
+        assert_eq!(tie.add_leg(Leg::new(result("B", "A", 0, 1))), Err(KnockoutError::Undecided));
+        assert_eq!(
+            tie.add_leg(Leg::new(result("B", "A", 0, 1)).with_extra_time(0, 0)),
+            Err(KnockoutError::Undecided)
+        );
+
+        tie.add_leg(Leg::new(result("B", "A", 0, 1)).with_extra_time(0, 0).with_penalties(3, 4)).unwrap();
+        assert_eq!(tie.winner(), Some("A"));
+        assert_eq!(tie.decided_by(), Some(Decision::Penalties));
+    }
+
+    #[test]
+    fn test_extra_time_rejected_when_not_needed() {
+        let mut tie = Tie::new("A".to_string(), "B".to_string(), TieFormat::SingleLeg);
+        let leg = Leg::new(result("A", "B", 2, 1)).with_extra_time(1, 0);
+        assert_eq!(tie.add_leg(leg), Err(KnockoutError::UnneededExtraTime));
+        assert!(tie.legs.is_empty());
+    }
+
+    #[test]
+    fn test_seeded_bracket_advances_to_a_champion() {
+        let teams: Vec<String> = (1..=4).map(|i| format!("Seed {}", i)).collect();
+        let mut bracket = Bracket::seeded_draw(&teams, TieFormat::SingleLeg, TieFormat::SingleLeg).unwrap();
+
+        let semis = &bracket.current_round().ties;
+        assert_eq!((semis[0].team1.as_str(), semis[0].team2.as_str()), ("Seed 1", "Seed 4"));
+        assert_eq!((semis[1].team1.as_str(), semis[1].team2.as_str()), ("Seed 2", "Seed 3"));
+
+        bracket.record_leg(Leg::new(result("Seed 1", "Seed 4", 2, 0))).unwrap();
+        bracket.record_leg(Leg::new(result("Seed 2", "Seed 3", 1, 1)).with_extra_time(0, 1)).unwrap();
+        assert_eq!(bracket.current_round().name, "Final");
+
+        bracket.record_leg(Leg::new(result("Seed 1", "Seed 3", 0, 0)).with_extra_time(0, 0).with_penalties(5, 4)).unwrap();
+        assert_eq!(bracket.champion(), Some("Seed 1"));
+        assert_eq!(Bracket::seeded_draw(&teams[..3], TieFormat::SingleLeg, TieFormat::SingleLeg).err(), Some(KnockoutError::BadTeamCount(3)));
+    }
+}
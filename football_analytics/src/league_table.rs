@@ -0,0 +1,255 @@
+use std::collections::HashMap;
+use std::fmt;
+
+use serde::Serialize;
+
+use crate::match_data::MatchData;
+use crate::record::Record;
+
+// Criteria used to order the table. They are applied in the order given in
+// `TableRules::order`; each one only separates teams still level on all the
+// previous ones.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum SortKey {
+    Points,
+    GoalDifference,
+    GoalsScored,
+    HeadToHead,     // Mini-league between the tied teams: points, then goal difference
+    AwayGoals,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct TableRules {
+    pub points_for_win: u32,
+    pub points_for_draw: u32,
+    pub order: Vec<SortKey>,
+}
+
+impl TableRules {
+    pub fn new(points_for_win: u32, points_for_draw: u32, order: Vec<SortKey>) -> Self {
+        TableRules {
+            points_for_win,
+            points_for_draw,
+            order,
+        }
+    }
+
+    // Points, goal difference, goals scored
+    pub fn premier_league() -> Self {
+        TableRules::new(3, 1, vec![SortKey::Points, SortKey::GoalDifference, SortKey::GoalsScored])
+    }
+
+    // Points, head-to-head, goal difference, goals scored
+    pub fn la_liga() -> Self {
+        TableRules::new(
+            3,
+            1,
+            vec![SortKey::Points, SortKey::HeadToHead, SortKey::GoalDifference, SortKey::GoalsScored],
+        )
+    }
+
+    // The pre-1995 system: two points for a win
+    pub fn two_points_for_a_win() -> Self {
+        TableRules::new(2, 1, vec![SortKey::Points, SortKey::GoalDifference, SortKey::GoalsScored])
+    }
+}
+
+impl Default for TableRules {
+    fn default() -> Self {
+        TableRules::premier_league()
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct TableRow {
+    pub position: usize,
+    pub team: String,
+    #[serde(flatten)]
+    pub record: Record,
+    pub away_goals: usize,
+    pub points: u32,
+}
+
+impl TableRow {
+    fn new(team: &str) -> Self {
+        TableRow {
+            position: 0,
+            team: team.to_string(),
+            record: Record::default(),
+            away_goals: 0,
+            points: 0,
+        }
+    }
+
+    pub fn goal_difference(&self) -> i64 {
+        self.record.goal_difference()
+    }
+
+    fn add(&mut self, scored: u8, conceded: u8, rules: &TableRules) {
+        self.record.add(scored, conceded);
+        self.points = self.record.points(rules.points_for_win, rules.points_for_draw);
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct LeagueTable {
+    rows: Vec<TableRow>,
+}
+
+impl LeagueTable {
+    pub fn from_matches(matches: &[MatchData], rules: &TableRules) -> Self {
+        let mut rows: HashMap<String, TableRow> = HashMap::new();
+
+        for m in matches {
+            let home = rows.entry(m.home_team.clone()).or_insert_with(|| TableRow::new(&m.home_team));
+            home.add(m.home_score, m.away_score, rules);
+
+            let away = rows.entry(m.away_team.clone()).or_insert_with(|| TableRow::new(&m.away_team));
+            away.add(m.away_score, m.home_score, rules);
+            away.away_goals += m.away_score as usize;
+        }
+
+        let mut rows: Vec<TableRow> = rows.into_values().collect();
+        // Alphabetical order is the last resort so the output is stable
+        rows.sort_by(|a, b| a.team.cmp(&b.team));
+        let mut rows = rank(rows, &rules.order, matches, rules);
+
+        for (i, row) in rows.iter_mut().enumerate() {
+            row.position = i + 1;
+        }
+
+        LeagueTable { rows }
+    }
+
+    pub fn rows(&self) -> &[TableRow] {
+        &self.rows
+    }
+
+    pub fn get(&self, team: &str) -> Option<&TableRow> {
+        self.rows.iter().find(|r| r.team == team)
+    }
+
+    pub fn leader(&self) -> Option<&TableRow> {
+        self.rows.first()
+    }
+}
+
+// Sorts `rows` by the first key, then recursively splits the rows that are
+// still level and sorts each group by the remaining keys. Head-to-head needs
+// this: it depends on which teams are tied, not on a team's own numbers.
+fn rank(rows: Vec<TableRow>, keys: &[SortKey], matches: &[MatchData], rules: &TableRules) -> Vec<TableRow> {
+    let (key, rest) = match keys.split_first() {
+        Some(split) if rows.len() > 1 => split,
+        _ => return rows,
+    };
+
+    let values = sort_values(&rows, *key, matches, rules);
+    let mut keyed: Vec<((i64, i64), TableRow)> = values.into_iter().zip(rows).collect();
+    keyed.sort_by_key(|k| std::cmp::Reverse(k.0));
+
+    let mut ranked = Vec::with_capacity(keyed.len());
+    let mut group: Vec<TableRow> = Vec::new();
+    let mut group_value = None;
+
+    for (value, row) in keyed {
+        if group_value != Some(value) && !group.is_empty() {
+            ranked.extend(rank(std::mem::take(&mut group), rest, matches, rules));
+        }
+        group_value = Some(value);
+        group.push(row);
+    }
+    ranked.extend(rank(group, rest, matches, rules));
+
+    ranked
+}
+
+fn sort_values(rows: &[TableRow], key: SortKey, matches: &[MatchData], rules: &TableRules) -> Vec<(i64, i64)> {
+    match key {
+        SortKey::Points => rows.iter().map(|r| (r.points as i64, 0)).collect(),
+        SortKey::GoalDifference => rows.iter().map(|r| (r.goal_difference(), 0)).collect(),
+        SortKey::GoalsScored => rows.iter().map(|r| (r.record.goals_for as i64, 0)).collect(),
+        SortKey::AwayGoals => rows.iter().map(|r| (r.away_goals as i64, 0)).collect(),
+        SortKey::HeadToHead => {
+            let tied: Vec<&str> = rows.iter().map(|r| r.team.as_str()).collect();
+            let between: Vec<MatchData> = matches
+                .iter()
+                .filter(|m| tied.contains(&m.home_team.as_str()) && tied.contains(&m.away_team.as_str()))
+                .cloned()
+                .collect();
+            let mini = LeagueTable::from_matches(&between, &TableRules::new(rules.points_for_win, rules.points_for_draw, Vec::new()));
+
+            rows.iter()
+                .map(|r| match mini.get(&r.team) {
+                    Some(h2h) => (h2h.points as i64, h2h.goal_difference()),
+                    None => (0, 0),
+                })
+                .collect()
+        }
+    }
+}
+
+impl fmt::Display for LeagueTable {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "{:>3}  {:<20} {:>3} {:>3} {:>3} {:>3} {:>4} {:>4} {:>4} {:>4}",
+                 "Pos", "Team", "P", "W", "D", "L", "GF", "GA", "GD", "Pts")?;
+        for r in &self.rows {
+            writeln!(f, "{:>3}  {:<20} {:>3} {:>3} {:>3} {:>3} {:>4} {:>4} {:>+4} {:>4}",
+                     r.position, r.team, r.record.played, r.record.won, r.record.drawn, r.record.lost,
+                     r.record.goals_for, r.record.goals_against, r.goal_difference(), r.points)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::result;
+
+    fn order(table: &LeagueTable) -> Vec<&str> {
+        table.rows().iter().map(|r| r.team.as_str()).collect()
+    }
+
+    #[test]
+    fn test_points_and_record() {
+        let matches = vec![result("A", "B", 2, 0), result("B", "C", 1, 1), result("C", "A", 3, 1)];
+        let table = LeagueTable::from_matches(&matches, &TableRules::premier_league());
+
+        let c = table.get("C").unwrap();
+        assert_eq!((c.record.played, c.record.won, c.record.drawn, c.record.lost, c.points), (2, 1, 1, 0, 4));
+        assert_eq!(c.goal_difference(), 2);
+        assert_eq!(c.away_goals, 1);
+        assert_eq!(order(&table), vec!["C", "A", "B"]);
+        assert_eq!(table.leader().unwrap().position, 1);
+    }
+
+    #[test]
+    fn test_two_points_for_a_win() {
+        let matches = vec![result("A", "B", 1, 0), result("C", "D", 0, 0)];
+        let table = LeagueTable::from_matches(&matches, &TableRules::two_points_for_a_win());
+        assert_eq!(table.get("A").unwrap().points, 2);
+        assert_eq!(table.get("C").unwrap().points, 1);
+    }
+
+    #[test]
+    fn test_head_to_head_beats_goal_difference() {
+        // A and C both finish on 3 points; A has the better goal difference
+        // but C won the meeting between them.
+        let matches = vec![result("A", "B", 1, 0), result("B", "C", 5, 0), result("A", "C", 0, 1), result("C", "B", 0, 1)];
+
+        let gd_first = LeagueTable::from_matches(&matches, &TableRules::premier_league());
+        assert_eq!(order(&gd_first), vec!["B", "A", "C"]);
+
+        let h2h_first = LeagueTable::from_matches(&matches, &TableRules::la_liga());
+        assert_eq!(order(&h2h_first), vec!["B", "C", "A"]);
+    }
+
+    #[test]
+    fn test_away_goals_tiebreaker() {
+        let matches = vec![result("A", "B", 1, 2), result("B", "A", 0, 1)];
+        let rules = TableRules::new(3, 1, vec![SortKey::Points, SortKey::GoalDifference, SortKey::AwayGoals]);
+        let table = LeagueTable::from_matches(&matches, &rules);
+        // Both on 3 points and level on goal difference; B scored 2 away, A 1
+        assert_eq!(order(&table), vec!["B", "A"]);
+    }
+}
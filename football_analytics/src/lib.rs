@@ -0,0 +1,33 @@
+pub mod analytics;
+pub mod cli;
+pub mod competition;
+pub mod date;
+pub mod discipline;
+pub mod elo;
+pub mod events;
+pub mod fixtures;
+pub mod form;
+pub mod formation;
+pub mod head_to_head;
+pub mod importer;
+pub mod knockout;
+pub mod league_table;
+pub mod lineup;
+pub mod match_data;
+pub mod persistence;
+pub mod player;
+pub mod predictor;
+pub mod ratings;
+pub mod record;
+pub mod registry;
+pub mod server;
+pub mod simulator;
+pub mod splits;
+pub mod squad;
+#[cfg(feature = "sqlite")]
+pub mod store;
+pub mod team;
+#[cfg(test)]
+mod test_util;
+pub mod transfers;
+pub mod xg;
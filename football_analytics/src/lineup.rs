@@ -0,0 +1,238 @@
+use std::collections::HashMap;
+
+use serde::{Deserialize, Serialize};
+
+use crate::events::{Card, Goal, Substitution};
+use crate::match_data::MatchData;
+
+// Regulation length; stoppage time isn't counted towards minutes played
+pub const MATCH_MINUTES: u32 = 90;
+
+// Who played for one side in a match. An empty lineup means it wasn't
+// recorded, and the match is left out of appearance numbers for that side.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct Lineup {
+    pub starters: Vec<String>,
+    pub substitutions: Vec<Substitution>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct PlayerAppearances {
+    pub appearances: usize,
+    pub starts: usize,
+    pub minutes: u32,
+    pub goals: usize,           // Only goals in matches the player appeared in
+    pub assists: usize,
+}
+
+impl Lineup {
+    pub fn new(starters: Vec<String>) -> Self {
+        Lineup {
+            starters,
+            substitutions: Vec::new(),
+        }
+    }
+
+    pub fn with_substitution(mut self, substitution: Substitution) -> Self {
+        self.substitutions.push(substitution);
+        self
+    }
+
+    pub fn is_recorded(&self) -> bool {
+        !self.starters.is_empty()
+    }
+
+    // Starters followed by substitutes in the order they came on
+    pub fn players(&self) -> Vec<&String> {
+        self.starters
+            .iter()
+            .chain(self.substitutions.iter().map(|s| &s.player_on))
+            .collect()
+    }
+
+    pub fn started(&self, player: &str) -> bool {
+        self.starters.iter().any(|p| p == player)
+    }
+
+    // Minute the player entered the match: 0 for starters, None if they
+    // never came on
+    pub fn came_on(&self, player: &str) -> Option<u32> {
+        if self.started(player) {
+            Some(0)
+        } else {
+            self.substitutions.iter().find(|s| s.player_on == player).map(|s| s.minute as u32)
+        }
+    }
+
+    // None if the player neither started nor came on
+    pub fn minutes_played(&self, player: &str) -> Option<u32> {
+        let on = self.came_on(player)?;
+        let off = self
+            .substitutions
+            .iter()
+            .find(|s| s.player_off == player)
+            .map(|s| s.minute as u32)
+            .unwrap_or(MATCH_MINUTES);
+        Some(off.min(MATCH_MINUTES).saturating_sub(on))
+    }
+}
+
+impl PlayerAppearances {
+    pub fn substitute_appearances(&self) -> usize {
+        self.appearances - self.starts
+    }
+
+    pub fn goals_per_90(&self) -> f64 {
+        per_90(self.goals, self.minutes)
+    }
+
+    pub fn assists_per_90(&self) -> f64 {
+        per_90(self.assists, self.minutes)
+    }
+
+    pub fn minutes_per_appearance(&self) -> f64 {
+        if self.appearances == 0 {
+            0.0
+        } else {
+            self.minutes as f64 / self.appearances as f64
+        }
+    }
+}
+
+// Keyed by player name
+pub fn appearances<'a, I>(matches: I) -> HashMap<String, PlayerAppearances>
+where
+    I: IntoIterator<Item = &'a MatchData>,
+{
+    let mut totals: HashMap<String, PlayerAppearances> = HashMap::new();
+
+    for m in matches {
+        let sides = [
+            (&m.home_lineup, &m.home_goals, &m.home_cards),
+            (&m.away_lineup, &m.away_goals, &m.away_cards),
+        ];
+        for (lineup, goals, cards) in sides {
+            if !lineup.is_recorded() {
+                continue;
+            }
+            for player in lineup.players() {
+                let entry = totals.entry(player.clone()).or_default();
+                entry.appearances += 1;
+                if lineup.started(player) {
+                    entry.starts += 1;
+                }
+                entry.minutes += minutes_on_pitch(lineup, cards, player);
+                entry.goals += goals.iter().filter(|g| scored(g, player)).count();
+                entry.assists += goals.iter().filter(|g| g.assist.as_deref() == Some(player.as_str())).count();
+            }
+        }
+    }
+
+    totals
+}
+
+// A sending off ends the player's match early
+fn minutes_on_pitch(lineup: &Lineup, cards: &[Card], player: &str) -> u32 {
+    let minutes = lineup.minutes_played(player).unwrap_or(0);
+    let on = lineup.came_on(player).unwrap_or(0);
+    match cards.iter().find(|c| c.player == player && c.is_sending_off()) {
+        Some(card) => minutes.min((card.minute as u32).saturating_sub(on)),
+        None => minutes,
+    }
+}
+
+fn scored(goal: &Goal, player: &str) -> bool {
+    !goal.is_own_goal() && goal.scorer == player
+}
+
+fn per_90(count: usize, minutes: u32) -> f64 {
+    if minutes == 0 {
+        0.0
+    } else {
+        count as f64 * 90.0 / minutes as f64
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::events::{CardKind, GoalKind};
+    use crate::test_util::{date, result};
+
+    #[test]
+    fn test_minutes_and_per_90() {
+        let home = Lineup::new(vec!["Keeper".to_string(), "Striker".to_string()])
+            .with_substitution(Substitution::new("Striker".to_string(), "Sub".to_string(), 60));
+        let m = MatchData::new(
+            date(2024, 1, 1),
+            "Home".to_string(),
+            "Away".to_string(),
+            2,
+            0,
+            vec![
+                Goal::new("Striker".to_string(), 30, GoalKind::OpenPlay),
+                Goal::new("Sub".to_string(), 75, GoalKind::OpenPlay).with_assist("Keeper".to_string()),
+            ],
+            vec![],
+        )
+        .with_lineups(home, Lineup::default());
+
+        assert_eq!(m.home_lineup.minutes_played("Sub"), Some(30));
+        assert_eq!(m.home_lineup.minutes_played("Nobody"), None);
+
+        let totals = appearances([&m]);
+        assert_eq!(totals.len(), 3);
+        assert_eq!(totals["Striker"].minutes, 60);
+        assert_eq!(totals["Sub"].substitute_appearances(), 1);
+        assert!((totals["Sub"].goals_per_90() - 3.0).abs() < 1e-9);
+        assert!((totals["Keeper"].assists_per_90() - 1.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_sending_off_ends_the_match_early() {
+        let home = Lineup::new(vec!["Defender".to_string(), "Winger".to_string()])
+            .with_substitution(Substitution::new("Winger".to_string(), "Sub".to_string(), 60));
+        let m = result("Home", "Away", 0, 1).with_lineups(home, Lineup::default()).with_cards(
+            vec![
+                Card::new("Defender".to_string(), 30, CardKind::Red),
+                Card::new("Sub".to_string(), 80, CardKind::SecondYellow),
+                Card::new("Ghost".to_string(), 50, CardKind::Red),
+            ],
+            vec![],
+        );
+
+        let totals = appearances([&m]);
+        assert_eq!(totals["Defender"].minutes, 30);
+        assert_eq!(totals["Sub"].minutes, 20);
+        assert_eq!(totals["Winger"].minutes, 60);
+        assert!(!totals.contains_key("Ghost"));
+        assert_eq!(totals.len(), 3);
+    }
+
+    #[test]
+    fn test_stoppage_time_substitutions() {
+        let lineup = Lineup::new(vec!["Striker".to_string()])
+            .with_substitution(Substitution::new("Striker".to_string(), "Sub".to_string(), 93));
+        assert_eq!(lineup.minutes_played("Striker"), Some(90));
+        assert_eq!(lineup.minutes_played("Sub"), Some(0));
+    }
+
+    #[test]
+    fn test_unrecorded_sides_are_skipped() {
+        let home = Lineup::new(vec!["Keeper".to_string()]);
+        let m = MatchData::new(
+            date(2024, 1, 1),
+            "Home".to_string(),
+            "Away".to_string(),
+            0,
+            1,
+            vec![],
+            vec![Goal::new("Away Striker".to_string(), 10, GoalKind::OpenPlay)],
+        )
+        .with_lineups(home, Lineup::default());
+
+        let totals = appearances([&m]);
+        assert_eq!(totals.keys().collect::<Vec<_>>(), vec!["Keeper"]);
+        assert_eq!(PlayerAppearances::default().minutes_per_appearance(), 0.0);
+    }
+}
@@ -1,14 +1,51 @@
-mod player;
-mod team;
-mod analytics;
-mod match_data;
+use std::io;
+use std::process;
 
-use crate::analytics::FootballAnalytics;
-use crate::match_data::MatchData;
-use crate::player::{Player, Position};
-use crate::team::Team;
+use football_analytics::analytics::FootballAnalytics;
+use football_analytics::cli::{self, Command};
+use football_analytics::competition::{Competition, CompetitionKind, Scope, Season};
+use football_analytics::date::{DateRange, MatchDate};
+use football_analytics::discipline::{discipline_table, SuspensionRules};
+use football_analytics::elo::EloConfig;
+use football_analytics::events::{BodyPart, Card, CardKind, Goal, GoalKind, Shot, ShotOutcome, ShotSituation, Substitution};
+use football_analytics::fixtures::{remaining_fixtures, round_robin, RoundRobin};
+use football_analytics::form::StreakKind;
+use football_analytics::knockout::{Bracket, Leg, TieFormat};
+use football_analytics::league_table::TableRules;
+use football_analytics::lineup::Lineup;
+use football_analytics::match_data::MatchData;
+use football_analytics::persistence::Dataset;
+use football_analytics::player::{Player, Position};
+use football_analytics::predictor::PoissonConfig;
+use football_analytics::ratings::RatingConfig;
+use football_analytics::registry::PlayerRegistry;
+use football_analytics::simulator::{EloScoreModel, SimulationConfig};
+use football_analytics::squad::SquadRules;
+use football_analytics::team::Team;
+use football_analytics::transfers::{Contract, SquadHistory, TransferTerms};
+use football_analytics::xg::XgModel;
 
 fn main() {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    let options = match cli::parse_args(&args) {
+        Ok(options) => options,
+        Err(e) => {
+            eprintln!("{}", e);
+            process::exit(2);
+        }
+    };
+
+    if options.command == Command::Demo {
+        demo();
+        return;
+    }
+    if let Err(e) = cli::run(&options, &mut io::stdout().lock()) {
+        eprintln!("error: {}", e);
+        process::exit(1);
+    }
+}
+
+fn demo() {
     println!("⚽ Football Analytics System");
     println!("============================\n");
 
@@ -17,40 +54,86 @@ fn main() {
     
     // Create Barcelona squad
     let mut barcelona = Team::new("FC Barcelona".to_string(), "Barcelona".to_string());
-    barcelona.add_player(Player::new("Lionel Messi".to_string(), 10, Position::Forward, 91));
-    barcelona.add_player(Player::new("Gerard Pique".to_string(), 3, Position::Defender, 85));
-    barcelona.add_player(Player::new("Sergio Busquets".to_string(), 5, Position::Midfielder, 88));
-    barcelona.add_player(Player::new("Marc-Andre ter Stegen".to_string(), 1, Position::Goalkeeper, 89));
+    barcelona.add_player(Player::new("Lionel Messi".to_string(), 10, Position::Forward, 91)).expect("valid squad");
+    barcelona.add_player(Player::new("Gerard Pique".to_string(), 3, Position::Defender, 85)).expect("valid squad");
+    barcelona.add_player(Player::new("Sergio Busquets".to_string(), 5, Position::Midfielder, 88)).expect("valid squad");
+    barcelona.add_player(Player::new("Marc-Andre ter Stegen".to_string(), 1, Position::Goalkeeper, 89)).expect("valid squad");
     
     // Create Real Madrid squad
     let mut real_madrid = Team::new("Real Madrid".to_string(), "Madrid".to_string());
-    real_madrid.add_player(Player::new("Karim Benzema".to_string(), 9, Position::Forward, 90));
-    real_madrid.add_player(Player::new("Sergio Ramos".to_string(), 4, Position::Defender, 89));
-    real_madrid.add_player(Player::new("Luka Modric".to_string(), 10, Position::Midfielder, 87));
-    real_madrid.add_player(Player::new("Thibaut Courtois".to_string(), 1, Position::Goalkeeper, 88));
+    real_madrid.add_player(Player::new("Karim Benzema".to_string(), 9, Position::Forward, 90)).expect("valid squad");
+    real_madrid.add_player(Player::new("Sergio Ramos".to_string(), 4, Position::Defender, 89)).expect("valid squad");
+    real_madrid.add_player(Player::new("Luka Modric".to_string(), 10, Position::Midfielder, 87)).expect("valid squad");
+    real_madrid.add_player(Player::new("Thibaut Courtois".to_string(), 1, Position::Goalkeeper, 88)).expect("valid squad");
     
     // Add sample match data
-    analytics.add_match(MatchData::new(
+    let mut clasico = MatchData::new(
+        MatchDate::new(2023, 10, 28).unwrap(),
         "FC Barcelona".to_string(),
         "Real Madrid".to_string(),
         3, 2,
-        vec!["Messi".to_string(), "Busquets".to_string(), "Pique".to_string()],
-        vec!["Benzema".to_string(), "Ramos".to_string()],
-    ));
+        vec![
+            Goal::new("Messi".to_string(), 12, GoalKind::OpenPlay).with_assist("Busquets".to_string()),
+            Goal::new("Busquets".to_string(), 45, GoalKind::OpenPlay).with_added_time(2),
+            Goal::new("Pique".to_string(), 78, GoalKind::OpenPlay).with_assist("Messi".to_string()),
+        ],
+        vec![
+            Goal::new("Benzema".to_string(), 30, GoalKind::Penalty),
+            Goal::new("Ramos".to_string(), 66, GoalKind::OpenPlay).with_assist("Modric".to_string()),
+        ],
+    );
+    clasico.home_shots = vec![
+        Shot::new("Messi".to_string(), 12, (94.0, 30.0), BodyPart::LeftFoot, ShotSituation::OpenPlay, ShotOutcome::Goal),
+        Shot::new("Messi".to_string(), 38, (80.0, 40.0), BodyPart::LeftFoot, ShotSituation::DirectFreeKick, ShotOutcome::Saved),
+        Shot::new("Busquets".to_string(), 45, (88.0, 34.0), BodyPart::RightFoot, ShotSituation::OpenPlay, ShotOutcome::Goal),
+        Shot::new("Pique".to_string(), 78, (100.0, 33.0), BodyPart::Head, ShotSituation::SetPiece, ShotOutcome::Goal),
+    ];
+    clasico.away_shots = vec![
+        Shot::new("Benzema".to_string(), 30, (94.0, 34.0), BodyPart::RightFoot, ShotSituation::Penalty, ShotOutcome::Goal),
+        Shot::new("Benzema".to_string(), 55, (85.0, 20.0), BodyPart::RightFoot, ShotSituation::Counter, ShotOutcome::OffTarget),
+        Shot::new("Ramos".to_string(), 66, (99.0, 36.0), BodyPart::Head, ShotSituation::SetPiece, ShotOutcome::Goal),
+    ];
+    clasico.home_lineup = Lineup::new(vec!["ter Stegen".to_string(), "Pique".to_string(), "Busquets".to_string(), "Messi".to_string()])
+        .with_substitution(Substitution::new("Busquets".to_string(), "Gavi".to_string(), 80));
+    clasico.away_lineup = Lineup::new(vec!["Courtois".to_string(), "Ramos".to_string(), "Modric".to_string(), "Benzema".to_string()])
+        .with_substitution(Substitution::new("Benzema".to_string(), "Vinicius".to_string(), 70));
+    clasico.home_cards = vec![Card::new("Busquets".to_string(), 33, CardKind::Yellow)];
+    clasico.away_cards = vec![Card::new("Ramos".to_string(), 58, CardKind::Yellow)];
+    analytics.add_match(clasico);
     
     analytics.add_match(MatchData::new(
+        MatchDate::new(2024, 4, 21).unwrap(),
         "Real Madrid".to_string(),
         "FC Barcelona".to_string(),
         1, 2,
-        vec!["Modric".to_string()],
-        vec!["Messi".to_string(), "Busquets".to_string()],
+        vec![Goal::new("Modric".to_string(), 51, GoalKind::FreeKick)],
+        vec![
+            Goal::new("Messi".to_string(), 20, GoalKind::OpenPlay).with_assist("Busquets".to_string()),
+            Goal::new("Busquets".to_string(), 88, GoalKind::OpenPlay),
+        ],
+    ).with_lineups(
+        Lineup::new(vec!["Courtois".to_string(), "Ramos".to_string(), "Modric".to_string(), "Benzema".to_string()]),
+        Lineup::new(vec!["ter Stegen".to_string(), "Pique".to_string(), "Gavi".to_string(), "Messi".to_string()])
+            .with_substitution(Substitution::new("Gavi".to_string(), "Busquets".to_string(), 60)),
+    ).with_cards(
+        vec![
+            Card::new("Ramos".to_string(), 40, CardKind::Yellow),
+            Card::new("Ramos".to_string(), 71, CardKind::SecondYellow),
+        ],
+        vec![Card::new("Pique".to_string(), 85, CardKind::Yellow)],
     ));
     
     analytics.add_match(MatchData::new(
+        MatchDate::new(2024, 3, 17).unwrap(),
         "FC Barcelona".to_string(),
         "Atletico Madrid".to_string(),
         4, 0,
-        vec!["Messi".to_string(), "Messi".to_string(), "Busquets".to_string(), "Pique".to_string()],
+        vec![
+            Goal::new("Messi".to_string(), 5, GoalKind::Penalty),
+            Goal::new("Messi".to_string(), 34, GoalKind::OpenPlay).with_assist("Busquets".to_string()),
+            Goal::new("Gimenez".to_string(), 60, GoalKind::OwnGoal),
+            Goal::new("Pique".to_string(), 90, GoalKind::OpenPlay).with_added_time(3),
+        ],
         vec![],
     ));
 
@@ -58,6 +141,27 @@ fn main() {
     println!("📊 Team Analysis:");
     println!("Barcelona - Average Rating: {:.1}", barcelona.average_rating());
     println!("Real Madrid - Average Rating: {:.1}", real_madrid.average_rating());
+    if let Err(e) = barcelona.add_player(Player::new("Ansu Fati".to_string(), 10, Position::Forward, 80)) {
+        println!("Could not add Ansu Fati: {}", e);
+    }
+    for error in barcelona.validate_for(&SquadRules::uefa()) {
+        println!("Barcelona UEFA registration: {}", error);
+    }
+
+    println!("\n📋 Best XI:");
+    for team in [&barcelona, &real_madrid] {
+        match team.best_formation(&["4-3-3", "4-4-2", "3-5-2"]) {
+            Ok(Some(xi)) => {
+                let names: Vec<&str> = xi.players().iter().map(|p| p.name.as_str()).collect();
+                println!("{} ({}): {} - combined rating {}", team.name, xi.formation, names.join(", "), xi.total_rating());
+                for (position, missing) in &xi.gaps {
+                    println!("  short of {} {}", missing, position);
+                }
+            }
+            Ok(None) => println!("{}: no formations to compare", team.name),
+            Err(e) => println!("{}: {}", team.name, e),
+        }
+    }
     
     println!("\n🏆 Top Scorers:");
     let top_scorers = analytics.get_top_scorers(3);
@@ -65,6 +169,33 @@ fn main() {
         println!("{}. {} - {} goals", i + 1, player, goals);
     }
     
+    println!("\n🅰️  Top Assists:");
+    for (player, assists) in analytics.get_top_assisters(3) {
+        println!("{} - {} assists", player, assists);
+    }
+    
+    println!("\n🔗 Goal Attribution:");
+    let mut registry = PlayerRegistry::new();
+    registry.register_team(&barcelona);
+    registry.register_team(&real_madrid);
+    let attribution = registry.attribute_goals(analytics.matches());
+    let mut goals_by_player: Vec<_> = attribution.goals_by_player().into_iter().collect();
+    goals_by_player.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+    for (id, goals) in goals_by_player {
+        if let Some(registered) = registry.get(id) {
+            println!("{} ({}) - {} goals", registered.player.name, registered.team, goals);
+        }
+    }
+    for issue in attribution.unresolved.iter().chain(attribution.ambiguous.iter()) {
+        println!("⚠️  {}: could not match '{}' ({})", issue.date, issue.name, issue.team);
+    }
+    
+    println!("\n🗓️  Top Scorers in 2024:");
+    let in_2024 = DateRange::between(MatchDate::new(2024, 1, 1).unwrap(), MatchDate::new(2024, 12, 31).unwrap());
+    for (player, goals) in analytics.get_top_scorers_in(3, &in_2024) {
+        println!("{} - {} goals", player, goals);
+    }
+    
     println!("\n📈 Team Performance:");
     let team_stats = analytics.get_team_stats();
     for (team, stats) in team_stats {
@@ -72,8 +203,249 @@ fn main() {
                  team, stats.0, stats.1, stats.2);
     }
     
+    println!("\n📋 League Table:");
+    print!("{}", analytics.league_table(&TableRules::premier_league()));
+    
+    println!("\n🏟️  Home / Away:");
+    for (label, table) in [("Home", analytics.home_table()), ("Away", analytics.away_table())] {
+        for (team, record) in table {
+            println!("{} {}: P{} W{} D{} L{} GF{} GA{} CS{} ({:.2} pts/game)", label, team,
+                     record.played, record.won, record.drawn, record.lost,
+                     record.goals_for, record.goals_against, record.clean_sheets,
+                     record.points_per_game());
+        }
+    }
+    let advantage = analytics.home_advantage();
+    println!("Home win rate {:.0}%, home advantage {:+.2} pts/game",
+             advantage.home_win_rate() * 100.0, advantage.points_advantage());
+    
+    println!("\n📉 Elo Ratings:");
+    let elo = analytics.elo_ratings(EloConfig::default());
+    for (team, rating) in elo.rankings() {
+        println!("{}: {:.0} ({:+.1} last 3)", team, rating, elo.recent_change(&team, 3));
+    }
+    
+    println!("\n⭐ Player Ratings (updated per match):");
+    let player_ratings = analytics.player_ratings(&[barcelona.clone(), real_madrid.clone()], RatingConfig::default());
+    for (id, rating) in player_ratings.rankings().into_iter().take(4) {
+        let Some(registered) = player_ratings.registry().get(id) else { continue };
+        let trail: Vec<String> = player_ratings.history(id).iter().map(|p| format!("{:.0}", p.match_score)).collect();
+        println!("{} ({}): {} -> {:.1}, match scores [{}]", registered.player.name, registered.team,
+                 registered.player.rating, rating, trail.join(", "));
+    }
+    let mut barcelona_now = barcelona.clone();
+    player_ratings.apply_to(&mut barcelona_now);
+    println!("Barcelona average rating: {:.1} entered, {:.1} on current form",
+             barcelona.average_rating(), barcelona_now.average_rating());
+    
+    println!("\n📐 Expected Goals (El Clasico, 2023-10-28):");
+    let xg_model = XgModel::default();
+    let clasico_only = DateRange::between(MatchDate::new(2023, 10, 28).unwrap(), MatchDate::new(2023, 10, 28).unwrap());
+    for m in analytics.matches_in(&clasico_only) {
+        println!("{} (xG {:.2} - {:.2})", m.match_summary(),
+                 xg_model.total_xg(&m.home_shots), xg_model.total_xg(&m.away_shots));
+    }
+    let mut player_xg: Vec<_> = analytics.player_xg(&xg_model).into_iter().collect();
+    player_xg.sort_by(|a, b| b.1.xg.total_cmp(&a.1.xg));
+    for (player, totals) in player_xg {
+        println!("{}: {} shots, {:.2} xG, {} goals ({:+.2})",
+                 player, totals.shots, totals.xg, totals.goals, totals.finishing_difference());
+    }
+    
+    println!("\n🔮 Prediction (Poisson / Dixon-Coles):");
+    let model = analytics.poisson_model(PoissonConfig::default());
+    if let Some(prediction) = model.predict("Real Madrid", "FC Barcelona") {
+        let (home_goals, away_goals, p) = prediction.most_likely_score();
+        println!("Real Madrid vs FC Barcelona: H {:.0}% / D {:.0}% / A {:.0}%",
+                 prediction.home_win * 100.0, prediction.draw * 100.0, prediction.away_win * 100.0);
+        println!("Most likely score: {}-{} ({:.1}%)", home_goals, away_goals, p * 100.0);
+    }
+    
+    println!("\n🔁 Transfers:");
+    let mut history = SquadHistory::new();
+    history.record_squad(&barcelona, MatchDate::new(2020, 7, 1).unwrap());
+    history.record_squad(&real_madrid, MatchDate::new(2020, 7, 1).unwrap());
+    let gavi = Player::new("Gavi".to_string(), 30, Position::Midfielder, 78);
+    let contract = Contract { until: MatchDate::new(2026, 6, 30).unwrap(), weekly_wage: None };
+    if let Err(e) = history.sign(&mut barcelona, gavi, MatchDate::new(2021, 8, 29).unwrap(), TransferTerms::free().with_contract(contract)) {
+        println!("Could not sign Gavi: {}", e);
+    }
+    let mut psg = Team::new("Paris Saint-Germain".to_string(), "Paris".to_string());
+    match history.transfer(&mut real_madrid, &mut psg, 4, 4, MatchDate::new(2021, 7, 8).unwrap(), TransferTerms::free()) {
+        Ok(transfer) => println!("{} moved to {} on {}", transfer.player.name,
+                                 transfer.to_team.as_deref().unwrap_or("no club"), transfer.date),
+        Err(e) => println!("Transfer failed: {}", e),
+    }
+    let season = DateRange::season(2020).unwrap();
+    let names: Vec<&str> = history.players_during("Real Madrid", &season).iter().map(|s| s.player.name.as_str()).collect();
+    println!("Real Madrid in 2020/21: {}", names.join(", "));
+    println!("Barcelona squad size: {} in Jan 2021, {} now",
+             history.team_as_of(&barcelona, MatchDate::new(2021, 1, 1).unwrap()).squad_size(),
+             barcelona.squad_size());
+    
+    println!("\n💾 Save & Load:");
+    let path = std::env::temp_dir().join("football_analytics_demo.json");
+    let dataset = Dataset::from_analytics(&[barcelona.clone(), real_madrid.clone()], &analytics);
+    match dataset.save(&path).and_then(|_| Dataset::load(&path)) {
+        Ok(loaded) => println!("Saved and reloaded {} teams and {} matches (schema v{}) via {}",
+                               loaded.teams.len(), loaded.matches.len(), loaded.schema_version, path.display()),
+        Err(e) => println!("Could not save dataset: {}", e),
+    }
+    
+    #[cfg(feature = "sqlite")]
+    {
+        use football_analytics::store::SqliteStore;
+        let stored = SqliteStore::open_in_memory().and_then(|mut store| {
+            store.save_team(&barcelona)?;
+            store.save_team(&real_madrid)?;
+            store.add_matches(analytics.matches())?;
+            let season = store.analytics_in(&DateRange::season(2023).unwrap())?;
+            Ok((store.teams()?.len(), season.matches().len()))
+        });
+        match stored {
+            Ok((teams, matches)) => println!("SQLite store: {} teams, {} matches in 2023/24", teams, matches),
+            Err(e) => println!("SQLite store failed: {}", e),
+        }
+    }
+    
+    println!("\n📅 Fixtures (double round robin):");
+    let atletico = Team::new("Atletico Madrid".to_string(), "Madrid".to_string());
+    let matchdays = round_robin(&[barcelona, real_madrid, atletico], RoundRobin::Double);
+    for matchday in matchdays.clone() {
+        let games: Vec<String> = matchday.fixtures.iter()
+            .map(|f| format!("{} v {}", f.home_team, f.away_team))
+            .collect();
+        let bye = matchday.bye.map(|team| format!(" (bye: {})", team)).unwrap_or_default();
+        println!("Matchday {}: {}{}", matchday.number, games.join(", "), bye);
+    }
+    
+    println!("\n🎲 Season Simulation (Elo, 2,000 runs):");
+    let remaining = remaining_fixtures(&matchdays, analytics.matches());
+    let config = SimulationConfig { iterations: 2_000, ..SimulationConfig::default() };
+    let forecast = analytics.simulate_season(&remaining, &EloScoreModel::new(&elo), &config);
+    for team in forecast.teams() {
+        println!("{}: title {:.1}%, bottom {:.1}%, avg position {:.2}", team,
+                 forecast.title_probability(&team) * 100.0,
+                 forecast.bottom_probability(&team, 1) * 100.0,
+                 forecast.average_position(&team));
+    }
+    
+    println!("\n🏆 Copa del Rey (knockout):");
+    let cup_teams: Vec<String> = ["FC Barcelona", "Real Madrid", "Atletico Madrid", "Sevilla"]
+        .iter().map(|t| t.to_string()).collect();
+    let mut cup = Bracket::seeded_draw(&cup_teams, TieFormat::TwoLegged { away_goals: false }, TieFormat::SingleLeg)
+        .expect("four teams make a valid bracket");
+    let cup_legs = vec![
+        Leg::new(MatchData::new(MatchDate::new(2024, 2, 6).unwrap(), "FC Barcelona".to_string(), "Sevilla".to_string(), 2, 0, vec![], vec![])),
+        Leg::new(MatchData::new(MatchDate::new(2024, 2, 7).unwrap(), "Real Madrid".to_string(), "Atletico Madrid".to_string(), 1, 1, vec![], vec![])),
+        Leg::new(MatchData::new(MatchDate::new(2024, 2, 27).unwrap(), "Sevilla".to_string(), "FC Barcelona".to_string(), 1, 0, vec![], vec![])),
+        Leg::new(MatchData::new(MatchDate::new(2024, 2, 28).unwrap(), "Atletico Madrid".to_string(), "Real Madrid".to_string(), 2, 2, vec![], vec![]))
+            .with_extra_time(0, 0)
+            .with_penalties(3, 5),
+        Leg::new(MatchData::new(MatchDate::new(2024, 4, 6).unwrap(), "FC Barcelona".to_string(), "Real Madrid".to_string(), 1, 1, vec![], vec![]))
+            .with_extra_time(1, 0),
+    ];
+    for leg in cup_legs {
+        if let Err(e) = cup.record_leg(leg) {
+            println!("Could not record leg: {}", e);
+        }
+    }
+    for round in cup.rounds() {
+        for tie in &round.ties {
+            let (team1_goals, team2_goals) = tie.aggregate();
+            match (tie.winner(), tie.decided_by()) {
+                (Some(winner), Some(decision)) => println!("{}: {} {}-{} {} ({} through {})", round.name,
+                                                           tie.team1, team1_goals, team2_goals, tie.team2, winner, decision),
+                _ => println!("{}: {} v {} still to be decided", round.name, tie.team1, tie.team2),
+            }
+        }
+    }
+    if let Some(champion) = cup.champion() {
+        println!("Winner: {}", champion);
+    }
+    
+    println!("\n⏱️  Appearances:");
+    let mut appearances: Vec<_> = analytics.appearances().into_iter().collect();
+    appearances.sort_by(|a, b| b.1.minutes.cmp(&a.1.minutes).then_with(|| a.0.cmp(&b.0)));
+    for (player, apps) in appearances.iter().take(5) {
+        println!(
+            "{}: {} apps ({} starts), {} min, {:.2} goals/90, {:.2} assists/90",
+            player, apps.appearances, apps.starts, apps.minutes, apps.goals_per_90(), apps.assists_per_90()
+        );
+    }
+    
+    println!("\n🟨 Discipline:");
+    for (team, record) in discipline_table(&analytics.team_discipline()) {
+        println!("{}: {} yellow, {} sent off ({} points)", team, record.yellows, record.sendings_off(), record.points());
+    }
+    let rules = SuspensionRules::default();
+    if let Some(next) = remaining.iter().find(|f| f.home_team == "Real Madrid" || f.away_team == "Real Madrid") {
+        println!("Unavailable for {} v {}:", next.home_team, next.away_team);
+        for ban in analytics.unavailable_for(next, &rules) {
+            println!("  {} ({}) - {}, {} match(es) left", ban.player, ban.team, ban.reason, ban.matches_remaining);
+        }
+    }
+    
+    println!("\n📈 Form & Streaks:");
+    for team in ["FC Barcelona", "Real Madrid"] {
+        let report = analytics.streaks(team);
+        println!("{}: form {}", team, analytics.form(team, 5));
+        for kind in StreakKind::ALL {
+            if let Some(longest) = report.longest(kind) {
+                let current = report.current(kind).map(|s| s.length).unwrap_or(0);
+                println!("  longest {}, current {}", longest, current);
+            }
+        }
+    }
+    
+    println!("\n🏅 Competitions & Seasons:");
+    let mut competitions = FootballAnalytics::new();
+    competitions.add_competition(Competition::new("La Liga".to_string(), CompetitionKind::League, Some("Spain".to_string())));
+    competitions.add_competition(Competition::new("Copa del Rey".to_string(), CompetitionKind::Cup, Some("Spain".to_string())));
+    for m in analytics.matches() {
+        competitions.add_match(m.clone().with_competition("La Liga", None));
+    }
+    competitions.add_match(MatchData::new(
+        MatchDate::new(2024, 1, 18).unwrap(),
+        "Athletic Club".to_string(),
+        "FC Barcelona".to_string(),
+        4, 2,
+        vec![],
+        vec![],
+    ).with_competition("Copa del Rey", None));
+    for competition in competitions.competitions() {
+        for season in competitions.seasons(Some(&competition.name)) {
+            let scoped = competitions.scoped(&Scope::competition(&competition.name).with_season(season));
+            let headline = match competition.kind {
+                CompetitionKind::League => scoped.league_table(&TableRules::default()).rows().first()
+                    .map(|r| format!("leader {}", r.team)),
+                CompetitionKind::Cup => scoped.matches().last().map(|m| format!("last tie {}", m.match_summary())),
+            };
+            println!("{} {}: {} matches, {}", competition.name, season, scoped.matches().len(),
+                     headline.unwrap_or_else(|| "no results".to_string()));
+        }
+    }
+    let all_seasons = competitions.scoped(&Scope::season(Season::new(2023).unwrap()));
+    println!("All competitions in 2023/24: {} matches", all_seasons.matches().len());
+    
     println!("\n🎯 Head-to-Head Analysis:");
-    if let Some((team1_wins, team2_wins, draws)) = analytics.head_to_head("FC Barcelona", "Real Madrid") {
-        println!("Barcelona vs Real Madrid: {}-{}-{} (W-L-D)", team1_wins, team2_wins, draws);
+    match analytics.head_to_head("FC Barcelona", "Real Madrid") {
+        Some(h2h) => {
+            println!("Barcelona vs Real Madrid: {}-{}-{} (W-L-D), goals {}-{}",
+                     h2h.team1_wins(), h2h.team2_wins(), h2h.draws(),
+                     h2h.overall.goals_for, h2h.overall.goals_against);
+            println!("At home: {}W {}D {}L, away: {}W {}D {}L",
+                     h2h.team1_at_home.won, h2h.team1_at_home.drawn, h2h.team1_at_home.lost,
+                     h2h.team1_away.won, h2h.team1_away.drawn, h2h.team1_away.lost);
+            if let Some(biggest) = &h2h.biggest_team1_win {
+                println!("Biggest win: {} ({})", biggest.match_summary(), biggest.date);
+            }
+            println!("Streak: {}", h2h.streak_summary());
+        }
+        None => println!("Barcelona and Real Madrid have never met"),
+    }
+    
+    if analytics.head_to_head("Real Madrid", "Atletico Madrid").is_none() {
+        println!("Real Madrid vs Atletico Madrid: never met");
     }
-}
\ No newline at end of file
+}
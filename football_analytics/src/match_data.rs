@@ -1,33 +1,128 @@
+use serde::{Deserialize, Serialize};
 
-#[derive(Debug, Clone)]
+use crate::competition::Season;
+use crate::date::{KickoffTime, MatchDate};
+use crate::events::{Card, Goal, Shot};
+use crate::lineup::Lineup;
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct MatchData {
+    pub date: MatchDate,
+    #[serde(default)]
+    pub kickoff: Option<KickoffTime>,
     pub home_team: String,
     pub away_team: String,
     pub home_score: u8,
     pub away_score: u8,
-    pub home_scorers: Vec<String>,    // Vector of scorer names
-    pub away_scorers: Vec<String>,    // Vector of scorer names
+    #[serde(default)]
+    pub home_goals: Vec<Goal>,        // Goals counting for the home side
+    #[serde(default)]
+    pub away_goals: Vec<Goal>,        // Goals counting for the away side
+    #[serde(default)]
+    pub home_shots: Vec<Shot>,
+    #[serde(default)]
+    pub away_shots: Vec<Shot>,
+    #[serde(default)]
+    pub home_lineup: Lineup,
+    #[serde(default)]
+    pub away_lineup: Lineup,
+    #[serde(default)]
+    pub home_cards: Vec<Card>,
+    #[serde(default)]
+    pub away_cards: Vec<Card>,
+    #[serde(default)]
+    pub competition: Option<String>,  // Competition name, None if untagged
+    #[serde(default)]
+    pub season: Option<Season>,       // None means the season the date falls in
 }
 
 impl MatchData {
     pub fn new(
+        date: MatchDate,
         home_team: String,
         away_team: String,
         home_score: u8,
         away_score: u8,
-        home_scorers: Vec<String>,
-        away_scorers: Vec<String>,
+        home_goals: Vec<Goal>,
+        away_goals: Vec<Goal>,
     ) -> Self {
         MatchData {
+            date,
+            kickoff: None,
             home_team,
             away_team,
             home_score,
             away_score,
-            home_scorers,
-            away_scorers,
+            home_goals,
+            away_goals,
+            home_shots: Vec::new(),
+            away_shots: Vec::new(),
+            home_lineup: Lineup::default(),
+            away_lineup: Lineup::default(),
+            home_cards: Vec::new(),
+            away_cards: Vec::new(),
+            competition: None,
+            season: None,
         }
     }
     
+    pub fn with_kickoff(mut self, kickoff: KickoffTime) -> Self {
+        self.kickoff = Some(kickoff);
+        self
+    }
+    
+    pub fn with_lineups(mut self, home_lineup: Lineup, away_lineup: Lineup) -> Self {
+        self.home_lineup = home_lineup;
+        self.away_lineup = away_lineup;
+        self
+    }
+    
+    pub fn with_cards(mut self, home_cards: Vec<Card>, away_cards: Vec<Card>) -> Self {
+        self.home_cards = home_cards;
+        self.away_cards = away_cards;
+        self
+    }
+    
+    // Leave `season` as None unless the competition doesn't run July to June
+    pub fn with_competition(mut self, competition: &str, season: Option<Season>) -> Self {
+        self.competition = Some(competition.to_string());
+        self.season = season;
+        self
+    }
+    
+    // None only for dates too early or late to have a season
+    pub fn season(&self) -> Option<Season> {
+        self.season.or_else(|| Season::containing(self.date))
+    }
+    
+    // Checks applied to results coming from outside (CSV import, the HTTP
+    // API). A goal list may be empty when scorers aren't known, but a
+    // filled one must have an entry per goal.
+    pub fn validate(&self) -> Result<(), String> {
+        if self.home_team == self.away_team {
+            return Err(format!("'{}' cannot play against itself", self.home_team));
+        }
+        let sides = [
+            (&self.home_team, &self.home_goals, self.home_score),
+            (&self.away_team, &self.away_goals, self.away_score),
+        ];
+        for (team, goals, score) in sides {
+            if !goals.is_empty() && goals.len() != score as usize {
+                return Err(format!("{} has {} scorers listed for {} goals", team, goals.len(), score));
+            }
+        }
+        Ok(())
+    }
+    
+    // Sort key used to keep matches in chronological order
+    pub fn kickoff_order(&self) -> (MatchDate, Option<KickoffTime>) {
+        (self.date, self.kickoff)
+    }
+    
+    pub fn involves(&self, team: &str) -> bool {
+        self.home_team == team || self.away_team == team
+    }
+    
     pub fn get_winner(&self) -> Option<String> {
         if self.home_score > self.away_score {
             Some(self.home_team.clone())
@@ -42,15 +137,33 @@ impl MatchData {
         self.home_score == self.away_score
     }
     
-    pub fn total_goals(&self) -> u8 {
-        self.home_score + self.away_score
+    // Widened so two u8 scores can't overflow
+    pub fn total_goals(&self) -> u16 {
+        self.home_score as u16 + self.away_score as u16
+    }
+    
+    // Every goal of the match in the order it was scored
+    pub fn all_goals(&self) -> Vec<&Goal> {
+        let mut goals: Vec<&Goal> = self.home_goals.iter().chain(self.away_goals.iter()).collect();
+        goals.sort_by_key(|g| g.time());
+        goals
     }
     
+    // Players credited with a goal, one entry per goal. Own goals don't
+    // count towards anyone's tally.
     pub fn get_all_scorers(&self) -> Vec<String> {
-        let mut all_scorers = Vec::new();
-        all_scorers.extend(self.home_scorers.clone());
-        all_scorers.extend(self.away_scorers.clone());
-        all_scorers
+        self.all_goals()
+            .into_iter()
+            .filter(|g| !g.is_own_goal())
+            .map(|g| g.scorer.clone())
+            .collect()
+    }
+    
+    pub fn get_all_assists(&self) -> Vec<String> {
+        self.all_goals()
+            .into_iter()
+            .filter_map(|g| g.assist.clone())
+            .collect()
     }
     
     pub fn match_summary(&self) -> String {
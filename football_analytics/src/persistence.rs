@@ -0,0 +1,243 @@
+use std::fmt;
+use std::fs;
+use std::path::Path;
+
+use serde::{Deserialize, Serialize};
+
+use crate::analytics::FootballAnalytics;
+use crate::competition::Competition;
+use crate::match_data::MatchData;
+use crate::squad::SquadError;
+use crate::team::{Team, TeamData};
+
+// Saves and loads a whole dataset as JSON:
+//
+//   { "schema_version": 2, "competitions": [...], "teams": [...], "matches": [...] }
+//
+// Dates are "YYYY-MM-DD" strings, kickoff times "HH:MM" and seasons
+// "2023/24". Match fields added after goals (kickoff, shots, lineups, cards,
+// competition, season) may be left out of hand-written files.
+//
+// Version 2 added competitions and the match competition/season tags. All
+// of them are optional, so version 1 files load as they are.
+
+// Bump whenever the file layout changes, and teach `from_json` to read the
+// older layouts
+pub const SCHEMA_VERSION: u32 = 2;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Dataset {
+    pub schema_version: u32,
+    #[serde(default)]
+    pub competitions: Vec<Competition>,
+    pub teams: Vec<Team>,
+    pub matches: Vec<MatchData>,    // Oldest first
+}
+
+// What `from_json` reads before checking each squad, so a bad squad can be
+// reported with its team's name
+#[derive(Deserialize)]
+struct RawDataset {
+    #[serde(default)]
+    competitions: Vec<Competition>,
+    teams: Vec<TeamData>,
+    matches: Vec<MatchData>,
+}
+
+#[derive(Debug)]
+pub enum PersistenceError {
+    Io(std::io::Error),
+    Json(serde_json::Error),
+    MissingVersion,
+    UnsupportedVersion(u32),        // Written by a newer version of this crate
+    Squad { team: String, error: SquadError },
+}
+
+impl Dataset {
+    pub fn new(teams: Vec<Team>, matches: Vec<MatchData>) -> Self {
+        Dataset {
+            schema_version: SCHEMA_VERSION,
+            competitions: Vec::new(),
+            teams,
+            matches,
+        }
+    }
+
+    pub fn from_analytics(teams: &[Team], analytics: &FootballAnalytics) -> Self {
+        Dataset {
+            competitions: analytics.competitions().to_vec(),
+            ..Dataset::new(teams.to_vec(), analytics.matches().to_vec())
+        }
+    }
+
+    pub fn into_analytics(self) -> (Vec<Team>, FootballAnalytics) {
+        let mut analytics = FootballAnalytics::new();
+        for competition in self.competitions {
+            analytics.add_competition(competition);
+        }
+        for m in self.matches {
+            analytics.add_match(m);
+        }
+        (self.teams, analytics)
+    }
+
+    pub fn to_json(&self) -> Result<String, PersistenceError> {
+        Ok(serde_json::to_string_pretty(self)?)
+    }
+
+    pub fn from_json(json: &str) -> Result<Self, PersistenceError> {
+        let value: serde_json::Value = serde_json::from_str(json)?;
+        let version = value
+            .get("schema_version")
+            .and_then(|v| v.as_u64())
+            .ok_or(PersistenceError::MissingVersion)? as u32;
+        if version > SCHEMA_VERSION {
+            return Err(PersistenceError::UnsupportedVersion(version));
+        }
+        let raw: RawDataset = serde_json::from_value(value)?;
+        let teams = raw
+            .teams
+            .into_iter()
+            .map(|data| {
+                let team = data.name.clone();
+                Team::try_from(data).map_err(|error| PersistenceError::Squad { team, error })
+            })
+            .collect::<Result<Vec<Team>, PersistenceError>>()?;
+        Ok(Dataset {
+            competitions: raw.competitions,
+            ..Dataset::new(teams, raw.matches)
+        })
+    }
+
+    pub fn save<P: AsRef<Path>>(&self, path: P) -> Result<(), PersistenceError> {
+        fs::write(path, self.to_json()?)?;
+        Ok(())
+    }
+
+    pub fn load<P: AsRef<Path>>(path: P) -> Result<Self, PersistenceError> {
+        Dataset::from_json(&fs::read_to_string(path)?)
+    }
+}
+
+impl fmt::Display for PersistenceError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PersistenceError::Io(e) => write!(f, "could not access dataset file: {}", e),
+            PersistenceError::Json(e) => write!(f, "invalid dataset JSON: {}", e),
+            PersistenceError::MissingVersion => write!(f, "dataset has no schema_version"),
+            PersistenceError::UnsupportedVersion(v) => {
+                write!(f, "dataset schema version {} is newer than the supported {}", v, SCHEMA_VERSION)
+            }
+            PersistenceError::Squad { team, error } => write!(f, "invalid squad for {}: {}", team, error),
+        }
+    }
+}
+
+impl std::error::Error for PersistenceError {}
+
+impl From<std::io::Error> for PersistenceError {
+    fn from(e: std::io::Error) -> Self {
+        PersistenceError::Io(e)
+    }
+}
+
+impl From<serde_json::Error> for PersistenceError {
+    fn from(e: serde_json::Error) -> Self {
+        PersistenceError::Json(e)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::competition::CompetitionKind;
+    use crate::date::KickoffTime;
+    use crate::events::{BodyPart, Card, CardKind, Goal, GoalKind, Shot, ShotOutcome, ShotSituation, Substitution};
+    use crate::lineup::Lineup;
+    use crate::player::Position;
+    use crate::test_util::{date, player};
+
+    #[test]
+    fn test_round_trip_is_lossless() {
+        let mut team = Team::new("FC Barcelona".to_string(), "Barcelona".to_string());
+        team.add_player(player("Lionel Messi", 10, Position::Forward, 91)).unwrap();
+
+        let mut m = MatchData::new(
+            date(2024, 4, 21),
+            "Real Madrid".to_string(),
+            "FC Barcelona".to_string(),
+            0,
+            1,
+            vec![],
+            vec![Goal::new("Messi".to_string(), 45, GoalKind::Penalty).with_added_time(2)],
+        )
+        .with_kickoff(KickoffTime::new(21, 0).unwrap())
+        .with_competition("La Liga", None)
+        .with_cards(vec![Card::new("Ramos".to_string(), 44, CardKind::Red)], vec![]);
+        m.away_shots = vec![Shot::new(
+            "Messi".to_string(),
+            45,
+            (94.0, 34.0),
+            BodyPart::LeftFoot,
+            ShotSituation::Penalty,
+            ShotOutcome::Goal,
+        )];
+        m.away_lineup = Lineup::new(vec!["Messi".to_string()])
+            .with_substitution(Substitution::new("Messi".to_string(), "Fati".to_string(), 80));
+
+        let mut dataset = Dataset::new(vec![team], vec![m]);
+        dataset.competitions = vec![Competition::new("La Liga".to_string(), CompetitionKind::League, Some("Spain".to_string()))];
+        let json = dataset.to_json().unwrap();
+        assert!(json.contains("\"date\": \"2024-04-21\""));
+
+        let loaded = Dataset::from_json(&json).unwrap();
+        assert_eq!(loaded.matches, dataset.matches);
+        assert_eq!(loaded.competitions, dataset.competitions);
+        assert_eq!(loaded.teams[0].players(), dataset.teams[0].players());
+    }
+
+    #[test]
+    fn test_versions_and_optional_fields() {
+        let json = r#"{
+            "schema_version": 1,
+            "teams": [],
+            "matches": [{
+                "date": "2024-03-17", "home_team": "A", "away_team": "B",
+                "home_score": 0, "away_score": 0, "home_goals": [], "away_goals": []
+            }]
+        }"#;
+        let dataset = Dataset::from_json(json).unwrap();
+        assert_eq!(dataset.schema_version, SCHEMA_VERSION);
+        let (_, analytics) = dataset.into_analytics();
+        assert_eq!(analytics.matches().len(), 1);
+        assert_eq!(analytics.matches()[0].competition, None);
+
+        assert!(matches!(
+            Dataset::from_json(r#"{"schema_version": 99, "teams": [], "matches": []}"#),
+            Err(PersistenceError::UnsupportedVersion(99))
+        ));
+        assert!(matches!(
+            Dataset::from_json(r#"{"teams": [], "matches": []}"#),
+            Err(PersistenceError::MissingVersion)
+        ));
+    }
+
+    #[test]
+    fn test_invalid_squads_are_rejected() {
+        let json = r#"{
+            "schema_version": 2,
+            "teams": [{"name": "X", "city": "Y", "players": [
+                {"name": "Keeper", "jersey_number": 1, "position": "Goalkeeper", "rating": 80},
+                {"name": "Impostor", "jersey_number": 1, "position": "Forward", "rating": 70}
+            ]}],
+            "matches": []
+        }"#;
+        match Dataset::from_json(json) {
+            Err(PersistenceError::Squad { team, error }) => {
+                assert_eq!(team, "X");
+                assert_eq!(error, SquadError::DuplicateJersey { jersey_number: 1, existing: "Keeper".to_string() });
+            }
+            other => panic!("expected a squad error, got {:?}", other),
+        }
+    }
+}
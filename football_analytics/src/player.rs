@@ -1,5 +1,6 @@
+use serde::{Deserialize, Serialize};
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct Player {
     pub name: String,           // UTF-8 encoded text
     pub jersey_number: u8,
@@ -7,7 +8,7 @@ pub struct Player {
     pub rating: u8,             // Overall rating (0-100)
 }
 
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub enum Position {
     Goalkeeper,
     Defender,
@@ -0,0 +1,323 @@
+use std::collections::HashMap;
+
+use crate::match_data::MatchData;
+
+// Poisson match model with the Dixon-Coles correction for low scores.
+//
+// Home goals ~ Poisson(attack[home] * defence[away] * home_advantage) and
+// away goals ~ Poisson(attack[away] * defence[home]). The plain model gets
+// 0-0, 1-0, 0-1 and 1-1 slightly wrong, so those four scorelines are
+// adjusted by a factor depending on `rho`.
+
+// Highest score a `MatchData` can hold
+pub const MAX_SCORE: usize = u8::MAX as usize;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct PoissonConfig {
+    pub max_goals: usize,       // Largest score per side in the probability matrix, capped at 255
+    pub iterations: usize,      // Fitting passes over the attack/defence equations
+}
+
+impl Default for PoissonConfig {
+    fn default() -> Self {
+        PoissonConfig {
+            max_goals: 10,
+            iterations: 100,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct TeamStrength {
+    pub attack: f64,            // 1.0 is league average
+    pub defence: f64,           // Goals conceded relative to average, lower is better
+}
+
+#[derive(Debug, Clone)]
+pub struct PoissonModel {
+    config: PoissonConfig,
+    strengths: HashMap<String, TeamStrength>,
+    home_advantage: f64,
+    rho: f64,
+}
+
+#[derive(Debug, Clone)]
+pub struct Prediction {
+    pub home_team: String,
+    pub away_team: String,
+    pub home_expected_goals: f64,
+    pub away_expected_goals: f64,
+    pub score_matrix: Vec<Vec<f64>>,    // [home_goals][away_goals]
+    pub home_win: f64,
+    pub draw: f64,
+    pub away_win: f64,
+}
+
+impl PoissonModel {
+    pub fn fit(matches: &[MatchData], config: PoissonConfig) -> Self {
+        let mut strengths: HashMap<String, TeamStrength> = HashMap::new();
+        for m in matches {
+            for team in [&m.home_team, &m.away_team] {
+                strengths.entry(team.clone()).or_insert(TeamStrength { attack: 1.0, defence: 1.0 });
+            }
+        }
+
+        let mut home_advantage = 1.0;
+        for _ in 0..config.iterations {
+            // Each update solves the maximum likelihood equation for one
+            // parameter while holding the others fixed
+            let mut attack_den: HashMap<&str, f64> = HashMap::new();
+            let mut defence_den: HashMap<&str, f64> = HashMap::new();
+            let mut scored: HashMap<&str, f64> = HashMap::new();
+            let mut conceded: HashMap<&str, f64> = HashMap::new();
+
+            for m in matches {
+                let home = strengths[&m.home_team];
+                let away = strengths[&m.away_team];
+
+                *attack_den.entry(&m.home_team).or_insert(0.0) += away.defence * home_advantage;
+                *attack_den.entry(&m.away_team).or_insert(0.0) += home.defence;
+                *defence_den.entry(&m.home_team).or_insert(0.0) += away.attack;
+                *defence_den.entry(&m.away_team).or_insert(0.0) += home.attack * home_advantage;
+
+                *scored.entry(&m.home_team).or_insert(0.0) += m.home_score as f64;
+                *scored.entry(&m.away_team).or_insert(0.0) += m.away_score as f64;
+                *conceded.entry(&m.home_team).or_insert(0.0) += m.away_score as f64;
+                *conceded.entry(&m.away_team).or_insert(0.0) += m.home_score as f64;
+            }
+
+            let mut updated = HashMap::new();
+            for (team, strength) in &strengths {
+                let attack = safe_div(scored[team.as_str()], attack_den[team.as_str()], strength.attack);
+                let defence = safe_div(conceded[team.as_str()], defence_den[team.as_str()], strength.defence);
+                updated.insert(team.clone(), TeamStrength { attack, defence });
+            }
+
+            // Attack and defence are only defined up to a common factor;
+            // pin the average attack to 1
+            let mean_attack = updated.values().map(|s| s.attack).sum::<f64>() / updated.len().max(1) as f64;
+            if mean_attack > 0.0 {
+                for s in updated.values_mut() {
+                    s.attack /= mean_attack;
+                    s.defence *= mean_attack;
+                }
+            }
+            strengths = updated;
+
+            let home_goals: f64 = matches.iter().map(|m| m.home_score as f64).sum();
+            let expected: f64 = matches
+                .iter()
+                .map(|m| strengths[&m.home_team].attack * strengths[&m.away_team].defence)
+                .sum();
+            home_advantage = safe_div(home_goals, expected, home_advantage);
+        }
+
+        let mut model = PoissonModel {
+            config,
+            strengths,
+            home_advantage,
+            rho: 0.0,
+        };
+        model.rho = model.fit_rho(matches);
+        model
+    }
+
+    pub fn strength(&self, team: &str) -> Option<TeamStrength> {
+        self.strengths.get(team).copied()
+    }
+
+    pub fn home_advantage(&self) -> f64 {
+        self.home_advantage
+    }
+
+    pub fn max_goals(&self) -> usize {
+        self.config.max_goals.min(MAX_SCORE)
+    }
+
+    pub fn rho(&self) -> f64 {
+        self.rho
+    }
+
+    // Expected goals for each side, None if either team has no history
+    pub fn expected_goals(&self, home: &str, away: &str) -> Option<(f64, f64)> {
+        let h = self.strengths.get(home)?;
+        let a = self.strengths.get(away)?;
+        Some((h.attack * a.defence * self.home_advantage, a.attack * h.defence))
+    }
+
+    pub fn predict(&self, home: &str, away: &str) -> Option<Prediction> {
+        let (lambda, mu) = self.expected_goals(home, away)?;
+        let score_matrix = score_matrix(lambda, mu, self.rho, self.config.max_goals);
+
+        let (mut home_win, mut draw, mut away_win) = (0.0, 0.0, 0.0);
+        for (h, row) in score_matrix.iter().enumerate() {
+            for (a, cell) in row.iter().enumerate() {
+                if h > a {
+                    home_win += *cell;
+                } else if h == a {
+                    draw += *cell;
+                } else {
+                    away_win += *cell;
+                }
+            }
+        }
+
+        Some(Prediction {
+            home_team: home.to_string(),
+            away_team: away.to_string(),
+            home_expected_goals: lambda,
+            away_expected_goals: mu,
+            score_matrix,
+            home_win,
+            draw,
+            away_win,
+        })
+    }
+
+    // Grid search for the rho that maximises the Dixon-Coles likelihood,
+    // keeping every correction factor positive
+    fn fit_rho(&self, matches: &[MatchData]) -> f64 {
+        let mut best = (0.0, f64::NEG_INFINITY);
+
+        for step in -40..=40 {
+            let rho = step as f64 * 0.005;
+            let mut log_likelihood = 0.0;
+
+            for m in matches {
+                let (lambda, mu) = match self.expected_goals(&m.home_team, &m.away_team) {
+                    Some(goals) => goals,
+                    None => continue,
+                };
+                let t = tau(m.home_score as usize, m.away_score as usize, lambda, mu, rho);
+                if t <= 0.0 {
+                    log_likelihood = f64::NEG_INFINITY;
+                    break;
+                }
+                log_likelihood += t.ln();
+            }
+
+            if log_likelihood > best.1 {
+                best = (rho, log_likelihood);
+            }
+        }
+
+        best.0
+    }
+}
+
+impl Prediction {
+    pub fn probability(&self, home_goals: usize, away_goals: usize) -> f64 {
+        self.score_matrix
+            .get(home_goals)
+            .and_then(|row| row.get(away_goals))
+            .copied()
+            .unwrap_or(0.0)
+    }
+
+    // (home goals, away goals, probability)
+    pub fn most_likely_score(&self) -> (usize, usize, f64) {
+        let mut best = (0, 0, 0.0);
+        for (h, row) in self.score_matrix.iter().enumerate() {
+            for (a, p) in row.iter().enumerate() {
+                if *p > best.2 {
+                    best = (h, a, *p);
+                }
+            }
+        }
+        best
+    }
+}
+
+// Scoreline probabilities [home_goals][away_goals] up to `max_goals` a side,
+// renormalised so the cut-off tail doesn't leak probability. A `rho` of 0
+// gives two independent Poisson distributions. Scores are stored as u8, so
+// `max_goals` above 255 is treated as 255.
+pub fn score_matrix(lambda: f64, mu: f64, rho: f64, max_goals: usize) -> Vec<Vec<f64>> {
+    let n = max_goals.min(MAX_SCORE) + 1;
+    let mut matrix = vec![vec![0.0; n]; n];
+    let mut total = 0.0;
+
+    for (h, row) in matrix.iter_mut().enumerate() {
+        for (a, cell) in row.iter_mut().enumerate() {
+            *cell = poisson_pmf(h, lambda) * poisson_pmf(a, mu) * tau(h, a, lambda, mu, rho);
+            total += *cell;
+        }
+    }
+
+    if total > 0.0 {
+        for cell in matrix.iter_mut().flatten() {
+            *cell /= total;
+        }
+    }
+    matrix
+}
+
+// Dixon-Coles adjustment; only the four lowest scorelines are affected.
+// Lopsided goal rates can push it below zero, which would make a negative
+// probability, so it stops at 0.
+fn tau(home_goals: usize, away_goals: usize, lambda: f64, mu: f64, rho: f64) -> f64 {
+    let tau = match (home_goals, away_goals) {
+        (0, 0) => 1.0 - lambda * mu * rho,
+        (0, 1) => 1.0 + lambda * rho,
+        (1, 0) => 1.0 + mu * rho,
+        (1, 1) => 1.0 - rho,
+        _ => 1.0,
+    };
+    tau.max(0.0)
+}
+
+fn poisson_pmf(k: usize, lambda: f64) -> f64 {
+    let mut p = (-lambda).exp();
+    for i in 1..=k {
+        p *= lambda / i as f64;
+    }
+    p
+}
+
+fn safe_div(numerator: f64, denominator: f64, fallback: f64) -> f64 {
+    if denominator > 0.0 {
+        numerator / denominator
+    } else {
+        fallback
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::result;
+
+    #[test]
+    fn test_stronger_team_is_favoured() {
+        let mut matches = Vec::new();
+        for _ in 0..5 {
+            matches.push(result("Strong", "Weak", 3, 0));
+            matches.push(result("Weak", "Strong", 1, 2));
+            matches.push(result("Strong", "Mid", 2, 1));
+            matches.push(result("Mid", "Weak", 1, 1));
+            matches.push(result("Weak", "Mid", 0, 1));
+            matches.push(result("Mid", "Strong", 0, 0));
+        }
+        let model = PoissonModel::fit(&matches, PoissonConfig::default());
+        let prediction = model.predict("Weak", "Strong").unwrap();
+
+        let total: f64 = prediction.score_matrix.iter().flatten().sum();
+        assert!((total - 1.0).abs() < 1e-9);
+        assert!((prediction.home_win + prediction.draw + prediction.away_win - 1.0).abs() < 1e-9);
+        assert!(prediction.away_win > prediction.home_win);
+        assert!(model.strength("Strong").unwrap().attack > model.strength("Weak").unwrap().attack);
+        assert!(model.predict("Weak", "Nobody").is_none());
+    }
+
+    #[test]
+    fn test_extreme_mismatch_stays_a_distribution() {
+        // 1 + 6.0 * -0.3 and 1 - 4.0 * 3.0 * 0.2 would both be negative
+        for (lambda, mu, rho) in [(6.0, 0.2, -0.3), (4.0, 3.0, 0.2)] {
+            let matrix = score_matrix(lambda, mu, rho, 10);
+            assert!(matrix.iter().flatten().all(|&p| p >= 0.0), "{:?}", (lambda, mu, rho));
+            let total: f64 = matrix.iter().flatten().sum();
+            assert!((total - 1.0).abs() < 1e-9);
+        }
+        assert_eq!(score_matrix(6.0, 0.2, -0.3, 10)[0][1], 0.0);
+    }
+}
@@ -0,0 +1,386 @@
+use std::collections::HashMap;
+
+use crate::date::MatchDate;
+use crate::events::CardKind;
+use crate::form::Outcome;
+use crate::match_data::MatchData;
+use crate::player::Position;
+use crate::registry::{NameMatch, PlayerId, PlayerRegistry};
+use crate::squad::MAX_RATING;
+use crate::team::Team;
+
+// Performance-driven player ratings. Every match gives each player who took
+// part a match score (a base mark plus the result, goals, assists, clean
+// sheets and cards); their rating then moves `k_factor` of the way towards
+// it. Players start from the rating entered in their squad.
+//
+// Match names are resolved through a `PlayerRegistry`, so add aliases for
+// any names it can't place. Without a recorded lineup only players who
+// scored, assisted or were booked count as having played.
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct RatingConfig {
+    pub k_factor: f64,          // Share of the gap to the match score closed per match
+    pub base_score: f64,        // Match score for turning up in a draw
+    pub win: f64,
+    pub loss: f64,
+    pub goal: f64,
+    pub assist: f64,
+    pub clean_sheet: f64,       // Goalkeepers and defenders only
+    pub yellow_card: f64,
+    pub red_card: f64,          // Also used for a second yellow
+}
+
+impl Default for RatingConfig {
+    fn default() -> Self {
+        RatingConfig {
+            k_factor: 0.2,
+            base_score: 65.0,
+            win: 8.0,
+            loss: -8.0,
+            goal: 12.0,
+            assist: 7.0,
+            clean_sheet: 10.0,
+            yellow_card: -4.0,
+            red_card: -15.0,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct PlayerRatingPoint {
+    pub date: MatchDate,
+    pub opponent: String,
+    pub match_score: f64,       // Performance in this match (0-100)
+    pub rating: f64,            // Rating after the match
+}
+
+#[derive(Debug)]
+pub struct PlayerRatings {
+    config: RatingConfig,
+    registry: PlayerRegistry,
+    ratings: HashMap<PlayerId, f64>,
+    history: HashMap<PlayerId, Vec<PlayerRatingPoint>>,
+}
+
+// What one player did in one match
+#[derive(Debug, Default)]
+struct Contribution {
+    goals: usize,
+    assists: usize,
+    yellows: usize,
+    sent_off: bool,
+}
+
+impl PlayerRatings {
+    pub fn new(registry: PlayerRegistry, config: RatingConfig) -> Self {
+        let ratings = registry
+            .players()
+            .iter()
+            .map(|p| (p.id, p.player.rating as f64))
+            .collect();
+        PlayerRatings {
+            config,
+            registry,
+            ratings,
+            history: HashMap::new(),
+        }
+    }
+
+    pub fn from_teams(teams: &[Team], config: RatingConfig) -> Self {
+        let mut registry = PlayerRegistry::new();
+        for team in teams {
+            registry.register_team(team);
+        }
+        PlayerRatings::new(registry, config)
+    }
+
+    // Starts every squad player at their entered rating, then applies each
+    // match in turn, so pass matches oldest first
+    pub fn from_matches(teams: &[Team], matches: &[MatchData], config: RatingConfig) -> Self {
+        let mut ratings = PlayerRatings::from_teams(teams, config);
+        for m in matches {
+            ratings.update(m);
+        }
+        ratings
+    }
+
+    pub fn update(&mut self, m: &MatchData) {
+        self.update_side(m, &m.home_team, &m.away_team);
+        self.update_side(m, &m.away_team, &m.home_team);
+    }
+
+    pub fn registry(&self) -> &PlayerRegistry {
+        &self.registry
+    }
+
+    pub fn rating(&self, id: PlayerId) -> Option<f64> {
+        self.ratings.get(&id).copied()
+    }
+
+    // Looks a squad player up by the name their team knows them by
+    pub fn rating_of(&self, team: &str, name: &str) -> Option<f64> {
+        match self.registry.resolve(name, Some(team)) {
+            NameMatch::Resolved(id) => self.rating(id),
+            _ => None,
+        }
+    }
+
+    pub fn history(&self, id: PlayerId) -> &[PlayerRatingPoint] {
+        self.history.get(&id).map(|h| h.as_slice()).unwrap_or(&[])
+    }
+
+    // Highest rated player first
+    pub fn rankings(&self) -> Vec<(PlayerId, f64)> {
+        let mut ranked: Vec<(PlayerId, f64)> = self.ratings.iter().map(|(id, r)| (*id, *r)).collect();
+        ranked.sort_by(|a, b| b.1.total_cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+        ranked
+    }
+
+    // Writes the current ratings back into the squad, rounded, so
+    // `Team::average_rating` and lineup picking use current form
+    pub fn apply_to(&self, team: &mut Team) {
+        let updates: Vec<(u8, u8)> = team
+            .players()
+            .iter()
+            .filter_map(|player| {
+                let registered = self
+                    .registry
+                    .players()
+                    .iter()
+                    .find(|p| p.team == team.name && p.player.name == player.name)?;
+                let rating = self.rating(registered.id)?;
+                Some((player.jersey_number, rating.round().clamp(0.0, MAX_RATING as f64) as u8))
+            })
+            .collect();
+        for (jersey_number, rating) in updates {
+            team.set_rating(jersey_number, rating).expect("ratings are clamped to MAX_RATING");
+        }
+    }
+
+    fn update_side(&mut self, m: &MatchData, team: &str, opponent: &str) {
+        let Some(outcome) = Outcome::for_team(m, team) else {
+            return;
+        };
+        let is_home = team == m.home_team;
+        let (goals, lineup, cards) = if is_home {
+            (&m.home_goals, &m.home_lineup, &m.home_cards)
+        } else {
+            (&m.away_goals, &m.away_lineup, &m.away_cards)
+        };
+        let conceded = if is_home { m.away_score } else { m.home_score };
+
+        let mut contributions: HashMap<PlayerId, Contribution> = HashMap::new();
+        if lineup.is_recorded() {
+            for name in lineup.players() {
+                if let Some(id) = self.resolve(name, team) {
+                    contributions.entry(id).or_default();
+                }
+            }
+        }
+        for goal in goals.iter().filter(|g| !g.is_own_goal()) {
+            if let Some(id) = self.resolve(&goal.scorer, team) {
+                contributions.entry(id).or_default().goals += 1;
+            }
+            if let Some(id) = goal.assist.as_ref().and_then(|a| self.resolve(a, team)) {
+                contributions.entry(id).or_default().assists += 1;
+            }
+        }
+        for card in cards {
+            if let Some(id) = self.resolve(&card.player, team) {
+                let contribution = contributions.entry(id).or_default();
+                match card.kind {
+                    CardKind::Yellow => contribution.yellows += 1,
+                    CardKind::SecondYellow | CardKind::Red => contribution.sent_off = true,
+                }
+            }
+        }
+
+        let result = match outcome {
+            Outcome::Win => self.config.win,
+            Outcome::Draw => 0.0,
+            Outcome::Loss => self.config.loss,
+        };
+        for (id, contribution) in contributions {
+            let defends = self
+                .registry
+                .get(id)
+                .is_some_and(|p| matches!(p.player.position, Position::Goalkeeper | Position::Defender));
+            let clean_sheet = if conceded == 0 && defends && !contribution.sent_off {
+                self.config.clean_sheet
+            } else {
+                0.0
+            };
+            let discipline = if contribution.sent_off {
+                self.config.red_card
+            } else {
+                self.config.yellow_card * contribution.yellows as f64
+            };
+            let match_score = (self.config.base_score
+                + result
+                + self.config.goal * contribution.goals as f64
+                + self.config.assist * contribution.assists as f64
+                + clean_sheet
+                + discipline)
+                .clamp(0.0, MAX_RATING as f64);
+
+            let old = self.ratings.get(&id).copied().unwrap_or(self.config.base_score);
+            let rating = old + self.config.k_factor * (match_score - old);
+            self.ratings.insert(id, rating);
+            self.history.entry(id).or_default().push(PlayerRatingPoint {
+                date: m.date,
+                opponent: opponent.to_string(),
+                match_score,
+                rating,
+            });
+        }
+    }
+
+    fn resolve(&self, name: &str, team: &str) -> Option<PlayerId> {
+        match self.registry.resolve(name, Some(team)) {
+            NameMatch::Resolved(id) => Some(id),
+            _ => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::events::{Card, Goal, GoalKind};
+    use crate::lineup::Lineup;
+    use crate::test_util::{date, player};
+
+    #[test]
+    fn test_ratings_follow_performances() {
+        let mut home = Team::new("Ajax".to_string(), "Amsterdam".to_string());
+        home.add_player(player("Remko Pasveer", 1, Position::Goalkeeper, 70)).unwrap();
+        home.add_player(player("Brian Brobbey", 9, Position::Forward, 70)).unwrap();
+        home.add_player(player("Steven Berghuis", 23, Position::Midfielder, 70)).unwrap();
+        let mut away = Team::new("PSV".to_string(), "Eindhoven".to_string());
+        away.add_player(player("Jordan Teze", 3, Position::Defender, 70)).unwrap();
+
+        let m = MatchData::new(
+            date(2024, 2, 18),
+            "Ajax".to_string(),
+            "PSV".to_string(),
+            2,
+            0,
+            vec![
+                Goal::new("Brobbey".to_string(), 12, GoalKind::OpenPlay).with_assist("Berghuis".to_string()),
+                Goal::new("Brobbey".to_string(), 70, GoalKind::OpenPlay),
+            ],
+            vec![],
+        )
+        .with_lineups(Lineup::new(vec!["Pasveer".to_string(), "Brobbey".to_string(), "Berghuis".to_string()]), Lineup::default())
+        .with_cards(vec![], vec![Card::new("Teze".to_string(), 55, CardKind::Red)]);
+
+        let ratings = PlayerRatings::from_matches(&[home.clone(), away.clone()], &[m], RatingConfig::default());
+        let brobbey = ratings.rating_of("Ajax", "Brobbey").unwrap();
+        let pasveer = ratings.rating_of("Ajax", "Pasveer").unwrap();
+        assert!(brobbey > pasveer && pasveer > 70.0);
+        assert!(ratings.rating_of("PSV", "Teze").unwrap() < 70.0);
+
+        let NameMatch::Resolved(id) = ratings.registry().resolve("Brobbey", Some("Ajax")) else {
+            panic!("Brobbey should resolve");
+        };
+        assert_eq!(ratings.history(id).len(), 1);
+        assert_eq!(ratings.history(id)[0].match_score, 65.0 + 8.0 + 24.0);
+
+        let before = home.average_rating();
+        ratings.apply_to(&mut home);
+        assert!(home.average_rating() > before);
+    }
+
+    fn squad(name: &str, players: &[(&str, u8, Position)]) -> Team {
+        let mut team = Team::new(name.to_string(), "City".to_string());
+        for (player_name, jersey, position) in players {
+            team.add_player(player(player_name, *jersey, position.clone(), 70)).unwrap();
+        }
+        team
+    }
+
+    fn id(ratings: &PlayerRatings, team: &str, name: &str) -> PlayerId {
+        match ratings.registry().resolve(name, Some(team)) {
+            NameMatch::Resolved(id) => id,
+            other => panic!("{} should resolve, got {:?}", name, other),
+        }
+    }
+
+    fn scored(day: u8, scorers: &[&str], away_score: u8) -> MatchData {
+        let goals = scorers.iter().map(|s| Goal::new(s.to_string(), 10, GoalKind::OpenPlay)).collect::<Vec<_>>();
+        MatchData::new(date(2024, 3, day), "Ajax".to_string(), "PSV".to_string(), goals.len() as u8, away_score, goals, vec![])
+    }
+
+    #[test]
+    fn test_history_across_matches() {
+        let teams = [squad("Ajax", &[("Brian Brobbey", 9, Position::Forward)]), squad("PSV", &[])];
+        let matches = [scored(1, &["Brobbey"], 0), scored(8, &[], 2), scored(15, &["Brobbey", "Brobbey"], 2)];
+        let ratings = PlayerRatings::from_matches(&teams, &matches, RatingConfig::default());
+
+        // Only matches with a goal count as played without a lineup
+        let history = ratings.history(id(&ratings, "Ajax", "Brobbey"));
+        assert_eq!(history.iter().map(|p| p.date).collect::<Vec<_>>(), vec![date(2024, 3, 1), date(2024, 3, 15)]);
+        assert_eq!(history[0].match_score, 65.0 + 8.0 + 12.0);
+        assert_eq!(history[1].match_score, 65.0 + 24.0);
+        assert!((history[0].rating - (70.0 + 0.2 * (85.0 - 70.0))).abs() < 1e-9);
+        assert!((history[1].rating - (history[0].rating + 0.2 * (89.0 - history[0].rating))).abs() < 1e-9);
+        assert_eq!(ratings.rating_of("Ajax", "Brobbey"), Some(history[1].rating));
+    }
+
+    #[test]
+    fn test_match_scores_are_clamped() {
+        let teams = [
+            squad("Ajax", &[("Brian Brobbey", 9, Position::Forward)]),
+            squad("PSV", &[("Jordan Teze", 3, Position::Defender)]),
+        ];
+        let m = scored(1, &["Brobbey", "Brobbey", "Brobbey"], 0)
+            .with_cards(vec![], vec![Card::new("Teze".to_string(), 20, CardKind::Red)]);
+        let config = RatingConfig { red_card: -100.0, ..RatingConfig::default() };
+        let ratings = PlayerRatings::from_matches(&teams, &[m], config);
+
+        assert_eq!(ratings.history(id(&ratings, "Ajax", "Brobbey"))[0].match_score, 100.0);
+        assert_eq!(ratings.history(id(&ratings, "PSV", "Teze"))[0].match_score, 0.0);
+    }
+
+    #[test]
+    fn test_clean_sheets_credit_defenders() {
+        let teams = [
+            squad(
+                "Ajax",
+                &[
+                    ("Remko Pasveer", 1, Position::Goalkeeper),
+                    ("Josip Sutalo", 37, Position::Defender),
+                    ("Brian Brobbey", 9, Position::Forward),
+                ],
+            ),
+            squad("PSV", &[]),
+        ];
+        let lineup = Lineup::new(vec!["Pasveer".to_string(), "Sutalo".to_string(), "Brobbey".to_string()]);
+        let m = scored(1, &["Brobbey"], 0).with_lineups(lineup, Lineup::default());
+        let ratings = PlayerRatings::from_matches(&teams, &[m], RatingConfig::default());
+
+        let score = |name: &str| ratings.history(id(&ratings, "Ajax", name))[0].match_score;
+        assert_eq!(score("Pasveer"), 65.0 + 8.0 + 10.0);
+        assert_eq!(score("Sutalo"), 65.0 + 8.0 + 10.0);
+        assert_eq!(score("Brobbey"), 65.0 + 8.0 + 12.0);
+    }
+
+    #[test]
+    fn test_unknown_and_ambiguous_names_are_skipped() {
+        let teams = [
+            squad(
+                "Ajax",
+                &[("Frenkie de Jong", 21, Position::Midfielder), ("Luuk de Jong", 9, Position::Forward)],
+            ),
+            squad("PSV", &[]),
+        ];
+        let m = scored(1, &["de Jong", "Stranger"], 0);
+        let ratings = PlayerRatings::from_matches(&teams, &[m], RatingConfig::default());
+
+        assert!(matches!(ratings.registry().resolve("de Jong", Some("Ajax")), NameMatch::Ambiguous(_)));
+        assert!(ratings.registry().players().iter().all(|p| ratings.history(p.id).is_empty()));
+        assert_eq!(ratings.rating_of("Ajax", "Frenkie de Jong"), Some(70.0));
+        assert_eq!(ratings.rating_of("Ajax", "Luuk de Jong"), Some(70.0));
+    }
+}
@@ -0,0 +1,57 @@
+use serde::Serialize;
+
+// Played / won / drawn / lost tally from one team's point of view
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize)]
+pub struct Record {
+    pub played: usize,
+    pub won: usize,
+    pub drawn: usize,
+    pub lost: usize,
+    pub goals_for: usize,
+    pub goals_against: usize,
+    pub clean_sheets: usize,
+}
+
+impl Record {
+    pub fn add(&mut self, scored: u8, conceded: u8) {
+        self.played += 1;
+        self.goals_for += scored as usize;
+        self.goals_against += conceded as usize;
+        if conceded == 0 {
+            self.clean_sheets += 1;
+        }
+
+        if scored > conceded {
+            self.won += 1;
+        } else if scored == conceded {
+            self.drawn += 1;
+        } else {
+            self.lost += 1;
+        }
+    }
+
+    pub fn goal_difference(&self) -> i64 {
+        self.goals_for as i64 - self.goals_against as i64
+    }
+
+    pub fn points(&self, points_for_win: u32, points_for_draw: u32) -> u32 {
+        self.won as u32 * points_for_win + self.drawn as u32 * points_for_draw
+    }
+
+    // Three points for a win
+    pub fn points_per_game(&self) -> f64 {
+        if self.played == 0 {
+            0.0
+        } else {
+            self.points(3, 1) as f64 / self.played as f64
+        }
+    }
+
+    pub fn win_rate(&self) -> f64 {
+        if self.played == 0 {
+            0.0
+        } else {
+            self.won as f64 / self.played as f64
+        }
+    }
+}
@@ -0,0 +1,283 @@
+use std::collections::HashMap;
+
+use crate::date::MatchDate;
+use crate::events::{Goal, GoalKind};
+use crate::match_data::MatchData;
+use crate::player::Player;
+use crate::team::Team;
+
+// Links the names used in match data ("Messi") to squad players
+// ("Lionel Messi"). Every registered player gets an id that never changes
+// and a set of aliases it can be looked up by.
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct PlayerId(pub u32);
+
+#[derive(Debug, Clone)]
+pub struct RegisteredPlayer {
+    pub id: PlayerId,
+    pub team: String,
+    pub player: Player,
+    pub aliases: Vec<String>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum NameMatch {
+    Resolved(PlayerId),
+    Ambiguous(Vec<PlayerId>),
+    Unresolved,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct GoalAttribution {
+    pub date: MatchDate,
+    pub team: String,           // Team the scorer plays for
+    pub scorer: PlayerId,
+    pub assist: Option<PlayerId>,
+    pub goal: Goal,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct NameIssue {
+    pub date: MatchDate,
+    pub team: String,
+    pub name: String,
+    pub candidates: Vec<PlayerId>,  // Empty when nobody matched
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct AttributionReport {
+    pub attributed: Vec<GoalAttribution>,
+    pub unresolved: Vec<NameIssue>,
+    pub ambiguous: Vec<NameIssue>,
+}
+
+#[derive(Debug, Default)]
+pub struct PlayerRegistry {
+    players: Vec<RegisteredPlayer>,
+    aliases: HashMap<String, Vec<PlayerId>>,
+}
+
+impl PlayerRegistry {
+    pub fn new() -> Self {
+        PlayerRegistry::default()
+    }
+
+    // Registers the full name, the surname and everything after the first
+    // name ("ter Stegen" for "Marc-Andre ter Stegen") as aliases
+    pub fn register(&mut self, team: &str, player: Player) -> PlayerId {
+        let id = PlayerId(self.players.len() as u32 + 1);
+        let words: Vec<&str> = player.name.split_whitespace().collect();
+
+        let mut aliases = vec![player.name.clone()];
+        if words.len() > 1 {
+            aliases.push(words[1..].join(" "));
+            aliases.push(words[words.len() - 1].to_string());
+        }
+
+        self.players.push(RegisteredPlayer {
+            id,
+            team: team.to_string(),
+            player,
+            aliases: Vec::new(),
+        });
+        for alias in aliases {
+            self.add_alias(id, &alias);
+        }
+
+        id
+    }
+
+    pub fn register_team(&mut self, team: &Team) -> Vec<PlayerId> {
+        team.players()
+            .iter()
+            .map(|p| self.register(&team.name, p.clone()))
+            .collect()
+    }
+
+    // Returns false if `id` isn't registered
+    pub fn add_alias(&mut self, id: PlayerId, alias: &str) -> bool {
+        let key = normalize(alias);
+        let entry = match self.players.iter_mut().find(|p| p.id == id) {
+            Some(entry) => entry,
+            None => return false,
+        };
+
+        let ids = self.aliases.entry(key).or_default();
+        if !ids.contains(&id) {
+            ids.push(id);
+            entry.aliases.push(alias.to_string());
+        }
+        true
+    }
+
+    pub fn get(&self, id: PlayerId) -> Option<&RegisteredPlayer> {
+        self.players.iter().find(|p| p.id == id)
+    }
+
+    pub fn players(&self) -> &[RegisteredPlayer] {
+        &self.players
+    }
+
+    // Looks `name` up among the aliases, narrowed to one team if given
+    pub fn resolve(&self, name: &str, team: Option<&str>) -> NameMatch {
+        let candidates: Vec<PlayerId> = self
+            .aliases
+            .get(&normalize(name))
+            .map(|ids| {
+                ids.iter()
+                    .copied()
+                    .filter(|id| team.is_none_or(|t| self.get(*id).is_some_and(|p| p.team == t)))
+                    .collect()
+            })
+            .unwrap_or_default();
+
+        match candidates.len() {
+            0 => NameMatch::Unresolved,
+            1 => NameMatch::Resolved(candidates[0]),
+            _ => NameMatch::Ambiguous(candidates),
+        }
+    }
+
+    // Attributes every goal in `matches` to a registered player. Run it on
+    // freshly imported matches to find names that need an alias.
+    pub fn attribute_goals(&self, matches: &[MatchData]) -> AttributionReport {
+        let mut report = AttributionReport::default();
+
+        for m in matches {
+            let sides = [
+                (&m.home_goals, &m.home_team, &m.away_team),
+                (&m.away_goals, &m.away_team, &m.home_team),
+            ];
+            for (goals, team, opponent) in sides {
+                for goal in goals {
+                    // An own goal was scored by a player of the other side
+                    let scorer_team = if goal.kind == GoalKind::OwnGoal { opponent } else { team };
+
+                    let scorer = self.resolve_or_report(&goal.scorer, scorer_team, m.date, &mut report);
+                    let assist = goal
+                        .assist
+                        .as_ref()
+                        .and_then(|name| self.resolve_or_report(name, team, m.date, &mut report));
+
+                    if let Some(scorer) = scorer {
+                        report.attributed.push(GoalAttribution {
+                            date: m.date,
+                            team: scorer_team.clone(),
+                            scorer,
+                            assist,
+                            goal: goal.clone(),
+                        });
+                    }
+                }
+            }
+        }
+
+        report
+    }
+
+    fn resolve_or_report(
+        &self,
+        name: &str,
+        team: &str,
+        date: MatchDate,
+        report: &mut AttributionReport,
+    ) -> Option<PlayerId> {
+        let issue = |candidates| NameIssue {
+            date,
+            team: team.to_string(),
+            name: name.to_string(),
+            candidates,
+        };
+
+        match self.resolve(name, Some(team)) {
+            NameMatch::Resolved(id) => Some(id),
+            NameMatch::Ambiguous(ids) => {
+                report.ambiguous.push(issue(ids));
+                None
+            }
+            NameMatch::Unresolved => {
+                report.unresolved.push(issue(Vec::new()));
+                None
+            }
+        }
+    }
+}
+
+impl AttributionReport {
+    pub fn is_clean(&self) -> bool {
+        self.unresolved.is_empty() && self.ambiguous.is_empty()
+    }
+
+    // Goals per player, own goals excluded
+    pub fn goals_by_player(&self) -> HashMap<PlayerId, usize> {
+        let mut goals = HashMap::new();
+        for a in self.attributed.iter().filter(|a| !a.goal.is_own_goal()) {
+            *goals.entry(a.scorer).or_insert(0) += 1;
+        }
+        goals
+    }
+}
+
+fn normalize(name: &str) -> String {
+    name.split_whitespace().collect::<Vec<_>>().join(" ").to_lowercase()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::player::Position;
+    use crate::test_util::{date, player};
+
+    fn registry() -> PlayerRegistry {
+        let mut registry = PlayerRegistry::new();
+        registry.register("FC Barcelona", player("Lionel Messi", 10, Position::Forward, 91));
+        registry.register("FC Barcelona", player("Marc-Andre ter Stegen", 1, Position::Goalkeeper, 89));
+        registry.register("Real Madrid", player("Sergio Ramos", 4, Position::Defender, 89));
+        registry.register("FC Barcelona", player("Sergio Busquets", 5, Position::Midfielder, 88));
+        registry
+    }
+
+    #[test]
+    fn test_resolves_surnames_and_aliases() {
+        let mut registry = registry();
+        assert_eq!(registry.resolve("messi", None), NameMatch::Resolved(PlayerId(1)));
+        assert_eq!(registry.resolve("ter Stegen", None), NameMatch::Resolved(PlayerId(2)));
+        assert_eq!(registry.resolve("Leo", None), NameMatch::Unresolved);
+
+        assert!(registry.add_alias(PlayerId(1), "Leo"));
+        assert_eq!(registry.resolve("Leo", None), NameMatch::Resolved(PlayerId(1)));
+    }
+
+    #[test]
+    fn test_team_narrows_ambiguous_names() {
+        let mut registry = registry();
+        registry.add_alias(PlayerId(3), "Sergio");
+        registry.add_alias(PlayerId(4), "Sergio");
+
+        assert_eq!(registry.resolve("Sergio", None), NameMatch::Ambiguous(vec![PlayerId(3), PlayerId(4)]));
+        assert_eq!(registry.resolve("Sergio", Some("Real Madrid")), NameMatch::Resolved(PlayerId(3)));
+    }
+
+    #[test]
+    fn test_own_goal_is_attributed_to_the_other_side() {
+        let registry = registry();
+        let m = MatchData::new(
+            date(2023, 10, 28),
+            "FC Barcelona".to_string(),
+            "Real Madrid".to_string(),
+            2, 0,
+            vec![
+                Goal::new("Messi".to_string(), 10, GoalKind::OpenPlay).with_assist("Xavi".to_string()),
+                Goal::new("Ramos".to_string(), 50, GoalKind::OwnGoal),
+            ],
+            vec![],
+        );
+
+        let report = registry.attribute_goals(&[m]);
+        assert_eq!(report.attributed.len(), 2);
+        assert_eq!(report.attributed[1].team, "Real Madrid");
+        assert_eq!(report.unresolved[0].name, "Xavi");
+        assert_eq!(report.goals_by_player().get(&PlayerId(3)), None);
+    }
+}
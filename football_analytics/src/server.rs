@@ -0,0 +1,537 @@
+use std::collections::HashMap;
+use std::io::{self, BufRead, BufReader, Read, Write};
+use std::net::{TcpListener, TcpStream};
+use std::path::PathBuf;
+use std::time::Duration;
+
+use serde_json::{json, Value};
+
+use crate::analytics::FootballAnalytics;
+use crate::competition::{Scope, Season};
+use crate::date::{DateRange, MatchDate};
+use crate::form;
+use crate::league_table::{LeagueTable, TableRules};
+use crate::match_data::MatchData;
+use crate::persistence::{Dataset, PersistenceError};
+use crate::team::Team;
+
+// Minimal JSON API over HTTP/1.1 for local dashboards. One request per
+// connection, handled in turn on a single thread.
+//
+//   GET  /teams                         teams on file
+//   GET  /matches?team=                 matches, oldest first
+//   GET  /table                         league table
+//   GET  /scorers?top=                  top scorers (default 10)
+//   GET  /h2h?team1=&team2=             head-to-head record
+//   GET  /stats?team=                   record, home/away split and form
+//   GET  /competitions                  registered competitions and their seasons
+//   POST /matches                       add a result (MatchData JSON)
+//
+// Every GET takes competition=NAME, season=2023/24 (or just 2023),
+// from=YYYY-MM-DD and to=YYYY-MM-DD to limit the matches looked at.
+
+const MAX_BODY: usize = 1 << 20;
+
+// Limits on the request line and headers together, so a client can't make
+// the server buffer an endless header section
+const MAX_HEADER_BYTES: u64 = 16 << 10;
+const MAX_HEADERS: usize = 100;
+
+// How long a client may leave a read or write waiting. Requests are served
+// one at a time, so a silent client would otherwise block everyone else.
+const DEFAULT_TIMEOUT: Duration = Duration::from_secs(5);
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Request {
+    pub method: String,
+    pub path: String,
+    pub query: HashMap<String, String>,
+    pub body: String,
+}
+
+// Why a request couldn't be read
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum BadRequest {
+    Malformed(&'static str),    // 400
+    HeadersTooLarge,            // 431
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Response {
+    pub status: u16,
+    pub body: Value,
+}
+
+pub struct Server {
+    teams: Vec<Team>,
+    analytics: FootballAnalytics,
+    data: Option<PathBuf>,          // Dataset file POSTed matches are saved to
+    timeout: Duration,
+}
+
+impl Request {
+    // The outer error is the connection failing; the inner one a request
+    // that can't be served
+    pub fn read<R: BufRead>(reader: &mut R) -> io::Result<Result<Request, BadRequest>> {
+        let mut head = reader.by_ref().take(MAX_HEADER_BYTES);
+        let mut request_line = String::new();
+        if !read_head_line(&mut head, &mut request_line)? {
+            return Ok(Err(BadRequest::HeadersTooLarge));
+        }
+        let mut parts = request_line.split_whitespace();
+        let (method, target) = match (parts.next(), parts.next()) {
+            (Some(method), Some(target)) => (method.to_string(), target.to_string()),
+            _ => return Ok(Err(BadRequest::Malformed("malformed request line"))),
+        };
+
+        let mut content_length = 0;
+        let mut headers = 0;
+        loop {
+            let mut header = String::new();
+            if !read_head_line(&mut head, &mut header)? {
+                return Ok(Err(BadRequest::HeadersTooLarge));
+            }
+            if header.trim().is_empty() {
+                break;
+            }
+            headers += 1;
+            if headers > MAX_HEADERS {
+                return Ok(Err(BadRequest::HeadersTooLarge));
+            }
+            if let Some((name, value)) = header.split_once(':') {
+                if name.trim().eq_ignore_ascii_case("content-length") {
+                    content_length = match value.trim().parse() {
+                        Ok(length) => length,
+                        Err(_) => return Ok(Err(BadRequest::Malformed("Content-Length must be a number"))),
+                    };
+                }
+            }
+        }
+        if content_length > MAX_BODY {
+            return Ok(Err(BadRequest::Malformed("request body too large")));
+        }
+
+        let mut body = vec![0; content_length];
+        reader.read_exact(&mut body)?;
+
+        let (path, query) = match target.split_once('?') {
+            Some((path, query)) => (path.to_string(), parse_query(query)),
+            None => (target, HashMap::new()),
+        };
+        Ok(Ok(Request {
+            method,
+            path,
+            query,
+            body: String::from_utf8_lossy(&body).into_owned(),
+        }))
+    }
+}
+
+// False if the line was cut off by the header size limit. A connection
+// closed before the blank line ends the headers like one.
+fn read_head_line<R: BufRead>(head: &mut io::Take<R>, line: &mut String) -> io::Result<bool> {
+    let read = head.read_line(line)?;
+    Ok(read == 0 || line.ends_with('\n') || head.limit() > 0)
+}
+
+impl BadRequest {
+    fn response(&self) -> Response {
+        match self {
+            BadRequest::Malformed(message) => Response::error(400, message),
+            BadRequest::HeadersTooLarge => Response::error(431, "request headers too large"),
+        }
+    }
+}
+
+impl Response {
+    fn ok(body: Value) -> Self {
+        Response { status: 200, body }
+    }
+
+    fn error(status: u16, message: &str) -> Self {
+        Response {
+            status,
+            body: json!({ "error": message }),
+        }
+    }
+
+    pub fn write<W: Write>(&self, out: &mut W) -> io::Result<()> {
+        let body = self.body.to_string();
+        write!(
+            out,
+            "HTTP/1.1 {} {}\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+            self.status,
+            reason(self.status),
+            body.len(),
+            body
+        )?;
+        out.flush()
+    }
+}
+
+impl Server {
+    pub fn new(teams: Vec<Team>, analytics: FootballAnalytics) -> Self {
+        Server {
+            teams,
+            analytics,
+            data: None,
+            timeout: DEFAULT_TIMEOUT,
+        }
+    }
+
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = timeout;
+        self
+    }
+
+    // Serves the dataset at `path` (empty if the file doesn't exist yet) and
+    // writes POSTed matches back to it
+    pub fn from_dataset(path: PathBuf) -> Result<Self, PersistenceError> {
+        let (teams, analytics) = if path.exists() {
+            Dataset::load(&path)?.into_analytics()
+        } else {
+            (Vec::new(), FootballAnalytics::new())
+        };
+        Ok(Server {
+            teams,
+            analytics,
+            data: Some(path),
+            timeout: DEFAULT_TIMEOUT,
+        })
+    }
+
+    // Blocks, answering requests until the process is stopped
+    pub fn serve(&mut self, port: u16) -> io::Result<()> {
+        let listener = TcpListener::bind(("127.0.0.1", port))?;
+        for stream in listener.incoming() {
+            // A client that hangs up mid-request shouldn't stop the server
+            if let Err(e) = stream.and_then(|s| self.handle_connection(s)) {
+                eprintln!("connection error: {}", e);
+            }
+        }
+        Ok(())
+    }
+
+    fn handle_connection(&mut self, mut stream: TcpStream) -> io::Result<()> {
+        stream.set_read_timeout(Some(self.timeout))?;
+        stream.set_write_timeout(Some(self.timeout))?;
+        let mut reader = BufReader::new(stream.try_clone()?);
+        let response = match Request::read(&mut reader) {
+            Ok(Ok(request)) => self.handle(&request),
+            Ok(Err(bad)) => bad.response(),
+            Err(e) if matches!(e.kind(), io::ErrorKind::WouldBlock | io::ErrorKind::TimedOut) => {
+                Response::error(408, "timed out waiting for the request")
+            }
+            Err(e) => return Err(e),
+        };
+        response.write(&mut stream)
+    }
+
+    pub fn handle(&mut self, request: &Request) -> Response {
+        match (request.method.as_str(), request.path.trim_end_matches('/')) {
+            ("POST", "/matches") => self.add_match(&request.body),
+            ("GET", path) => match filters(&request.query) {
+                Ok((scope, range)) => {
+                    let scoped;
+                    let analytics = if scope == Scope::all() {
+                        &self.analytics
+                    } else {
+                        scoped = self.analytics.scoped(&scope);
+                        &scoped
+                    };
+                    self.get(analytics, path, &request.query, &range)
+                }
+                Err(message) => Response::error(400, &message),
+            },
+            (_, "/teams" | "/matches" | "/table" | "/scorers" | "/h2h" | "/stats" | "/competitions") => {
+                Response::error(405, "method not allowed")
+            }
+            _ => Response::error(404, "no such endpoint"),
+        }
+    }
+
+    fn get(&self, analytics: &FootballAnalytics, path: &str, query: &HashMap<String, String>, range: &DateRange) -> Response {
+        let matches = analytics.matches_in(range);
+        match path {
+            "/teams" => Response::ok(json!(self.teams)),
+            "/competitions" => {
+                let competitions: Vec<Value> = analytics
+                    .competitions()
+                    .iter()
+                    .map(|c| {
+                        let seasons: Vec<String> = analytics.seasons(Some(&c.name)).iter().map(|s| s.to_string()).collect();
+                        json!({ "name": c.name, "kind": c.kind, "country": c.country, "seasons": seasons })
+                    })
+                    .collect();
+                Response::ok(json!(competitions))
+            }
+            "/matches" => {
+                let team = query.get("team");
+                let matches: Vec<&MatchData> = matches
+                    .into_iter()
+                    .filter(|m| team.is_none_or(|t| m.involves(t)))
+                    .collect();
+                Response::ok(json!(matches))
+            }
+            "/table" => {
+                let matches: Vec<MatchData> = matches.into_iter().cloned().collect();
+                let table = LeagueTable::from_matches(&matches, &TableRules::default());
+                Response::ok(json!(table.rows()))
+            }
+            "/scorers" => {
+                let top = match query.get("top").map(|t| t.parse::<usize>()) {
+                    Some(Ok(top)) => top,
+                    Some(Err(_)) => return Response::error(400, "top must be a number"),
+                    None => 10,
+                };
+                let scorers: Vec<Value> = analytics
+                    .get_top_scorers_in(top, range)
+                    .into_iter()
+                    .map(|(player, goals)| json!({ "player": player, "goals": goals }))
+                    .collect();
+                Response::ok(json!(scorers))
+            }
+            "/h2h" => {
+                let (Some(team1), Some(team2)) = (query.get("team1"), query.get("team2")) else {
+                    return Response::error(400, "team1 and team2 are required");
+                };
+                match analytics.head_to_head_in(team1, team2, range) {
+                    Some(h2h) => Response::ok(json!({
+                        "team1": h2h.team1,
+                        "team2": h2h.team2,
+                        "overall": h2h.overall,
+                        "team1_at_home": h2h.team1_at_home,
+                        "team1_away": h2h.team1_away,
+                        "team1_wins": h2h.team1_wins(),
+                        "draws": h2h.draws(),
+                        "team2_wins": h2h.team2_wins(),
+                        "streak": h2h.streak_summary(),
+                        "meetings": h2h.meetings,
+                    })),
+                    None => Response::error(404, "these teams have never met"),
+                }
+            }
+            "/stats" => {
+                let Some(team) = query.get("team") else {
+                    return Response::error(400, "team is required");
+                };
+                let splits = analytics.home_away_splits_in(range);
+                let Some(split) = splits.into_iter().find(|s| &s.team == team) else {
+                    return Response::error(404, "no matches for this team");
+                };
+                let played: Vec<&MatchData> = matches.into_iter().filter(|m| m.involves(team)).collect();
+                let last_five = &played[played.len().saturating_sub(5)..];
+                Response::ok(json!({
+                    "team": team,
+                    "overall": split.overall(),
+                    "home": split.home,
+                    "away": split.away,
+                    "form": form::form_string(team, last_five.iter().copied()),
+                }))
+            }
+            _ => Response::error(404, "no such endpoint"),
+        }
+    }
+
+    fn add_match(&mut self, body: &str) -> Response {
+        let m: MatchData = match serde_json::from_str(body) {
+            Ok(m) => m,
+            Err(e) => return Response::error(400, &format!("invalid match: {}", e)),
+        };
+        if let Err(message) = m.validate() {
+            return Response::error(400, &message);
+        }
+
+        self.analytics.add_match(m.clone());
+        if let Some(path) = &self.data {
+            if let Err(e) = Dataset::from_analytics(&self.teams, &self.analytics).save(path) {
+                return Response::error(500, &format!("match added but not saved: {}", e));
+            }
+        }
+        Response { status: 201, body: json!(m) }
+    }
+}
+
+fn filters(query: &HashMap<String, String>) -> Result<(Scope, DateRange), String> {
+    let date = |key: &str| -> Result<Option<MatchDate>, String> {
+        query
+            .get(key)
+            .map(|v| MatchDate::parse(v).ok_or_else(|| format!("{} must be a date like 2024-01-31", key)))
+            .transpose()
+    };
+
+    let season = query
+        .get("season")
+        .map(|s| Season::parse(s).ok_or_else(|| "season must look like 2023/24 or 2023".to_string()))
+        .transpose()?;
+    let scope = Scope {
+        competition: query.get("competition").cloned(),
+        season,
+    };
+    let range = DateRange {
+        from: date("from")?,
+        to: date("to")?,
+    };
+    Ok((scope, range))
+}
+
+fn parse_query(query: &str) -> HashMap<String, String> {
+    query
+        .split('&')
+        .filter(|pair| !pair.is_empty())
+        .map(|pair| {
+            let (key, value) = pair.split_once('=').unwrap_or((pair, ""));
+            (percent_decode(key), percent_decode(value))
+        })
+        .collect()
+}
+
+// "FC+Barcelona" and "FC%20Barcelona" both become "FC Barcelona"
+fn percent_decode(s: &str) -> String {
+    let bytes = s.as_bytes();
+    let hex = |i: usize| bytes.get(i).and_then(|b| (*b as char).to_digit(16));
+    let mut decoded = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        match (bytes[i], hex(i + 1), hex(i + 2)) {
+            (b'+', _, _) => decoded.push(b' '),
+            (b'%', Some(high), Some(low)) => {
+                decoded.push((high * 16 + low) as u8);
+                i += 2;
+            }
+            (byte, _, _) => decoded.push(byte),
+        }
+        i += 1;
+    }
+    String::from_utf8_lossy(&decoded).into_owned()
+}
+
+fn reason(status: u16) -> &'static str {
+    match status {
+        200 => "OK",
+        201 => "Created",
+        400 => "Bad Request",
+        404 => "Not Found",
+        405 => "Method Not Allowed",
+        408 => "Request Timeout",
+        431 => "Request Header Fields Too Large",
+        _ => "Internal Server Error",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::events::{Goal, GoalKind};
+    use crate::test_util::date;
+
+    fn get(server: &mut Server, target: &str) -> Response {
+        let raw = format!("GET {} HTTP/1.1\r\nHost: localhost\r\n\r\n", target);
+        let request = Request::read(&mut raw.as_bytes()).unwrap().unwrap();
+        server.handle(&request)
+    }
+
+    #[test]
+    fn test_query_and_post() {
+        let mut server = Server::new(Vec::new(), FootballAnalytics::new());
+        let m = MatchData::new(
+            date(2024, 4, 21),
+            "Real Madrid".to_string(),
+            "FC Barcelona".to_string(),
+            0,
+            1,
+            vec![],
+            vec![Goal::new("Messi".to_string(), 45, GoalKind::OpenPlay)],
+        );
+        let body = serde_json::to_string(&m).unwrap();
+        let raw = format!("POST /matches HTTP/1.1\r\nContent-Length: {}\r\n\r\n{}", body.len(), body);
+        let request = Request::read(&mut raw.as_bytes()).unwrap().unwrap();
+        assert_eq!(server.handle(&request).status, 201);
+
+        let scorers = get(&mut server, "/scorers?top=5&season=2023");
+        assert_eq!(scorers.body, json!([{ "player": "Messi", "goals": 1 }]));
+        assert_eq!(get(&mut server, "/scorers?season=2024/25").body, json!([]));
+        assert_eq!(get(&mut server, "/scorers?competition=La%20Liga").body, json!([]));
+        assert_eq!(get(&mut server, "/scorers?season=soon").status, 400);
+        assert_eq!(get(&mut server, "/scorers?season=65535/00").status, 400);
+
+        let h2h = get(&mut server, "/h2h?team1=FC+Barcelona&team2=Real%20Madrid");
+        assert_eq!(h2h.status, 200);
+        assert_eq!(h2h.body["team1_wins"], 1);
+
+        let stats = get(&mut server, "/stats?team=FC%20Barcelona");
+        assert_eq!(stats.body["form"], "W");
+        assert_eq!(get(&mut server, "/table").body[0]["team"], "FC Barcelona");
+
+        assert_eq!(get(&mut server, "/matches?from=21/04/2024").body.as_array().unwrap().len(), 1);
+        assert_eq!(get(&mut server, "/matches?from=yesterday").status, 400);
+        assert_eq!(get(&mut server, "/nowhere").status, 404);
+    }
+
+    fn post(server: &mut Server, body: &str) -> Response {
+        let raw = format!("POST /matches HTTP/1.1\r\nContent-Length: {}\r\n\r\n{}", body.len(), body);
+        let request = Request::read(&mut raw.as_bytes()).unwrap().unwrap();
+        server.handle(&request)
+    }
+
+    #[test]
+    fn test_post_validation_matches_the_importer() {
+        let mut server = Server::new(Vec::new(), FootballAnalytics::new());
+        let result = |home: &str, away: &str, goals: &str| {
+            format!(
+                r#"{{"date": "2024-03-17", "home_team": "{}", "away_team": "{}", "home_score": 2, "away_score": 0{}}}"#,
+                home, away, goals
+            )
+        };
+
+        // Scorers unknown: goal lists empty or left out
+        assert_eq!(post(&mut server, &result("A", "B", r#", "home_goals": [], "away_goals": []"#)).status, 201);
+        assert_eq!(post(&mut server, &result("C", "D", "")).status, 201);
+
+        let one_scorer = r#", "home_goals": [{"scorer": "X", "minute": 10, "added_time": 0, "kind": "OpenPlay"}]"#;
+        let response = post(&mut server, &result("E", "F", one_scorer));
+        assert_eq!(response.status, 400);
+        assert_eq!(response.body["error"], "E has 1 scorers listed for 2 goals");
+
+        assert_eq!(post(&mut server, &result("A", "A", "")).status, 400);
+        assert_eq!(server.analytics.matches().len(), 2);
+    }
+
+    #[test]
+    fn test_slow_clients_time_out() {
+        let mut server = Server::new(Vec::new(), FootballAnalytics::new()).with_timeout(Duration::from_millis(50));
+        let listener = TcpListener::bind(("127.0.0.1", 0)).unwrap();
+        let address = listener.local_addr().unwrap();
+
+        // Promises a longer body than it sends, then goes quiet
+        let mut client = TcpStream::connect(address).unwrap();
+        client.write_all(b"POST /matches HTTP/1.1\r\nContent-Length: 100\r\n\r\n{}").unwrap();
+        let (stream, _) = listener.accept().unwrap();
+        server.handle_connection(stream).unwrap();
+
+        let mut reply = String::new();
+        io::Read::read_to_string(&mut client, &mut reply).unwrap();
+        assert!(reply.starts_with("HTTP/1.1 408 Request Timeout"));
+    }
+
+    #[test]
+    fn test_header_limits() {
+        let read = |raw: String| Request::read(&mut raw.as_bytes()).unwrap();
+
+        let long_header = format!("GET /teams HTTP/1.1\r\nX-Padding: {}\r\n\r\n", "a".repeat(MAX_HEADER_BYTES as usize));
+        assert_eq!(read(long_header), Err(BadRequest::HeadersTooLarge));
+        let long_target = format!("GET /{} HTTP/1.1\r\n\r\n", "a".repeat(MAX_HEADER_BYTES as usize));
+        assert_eq!(read(long_target), Err(BadRequest::HeadersTooLarge));
+        let many_headers = format!("GET /teams HTTP/1.1\r\n{}\r\n", "X-A: b\r\n".repeat(MAX_HEADERS + 1));
+        assert_eq!(read(many_headers), Err(BadRequest::HeadersTooLarge));
+        assert_eq!(
+            read(format!("GET /teams HTTP/1.1\r\n{}\r\n", "X-A: b\r\n".repeat(MAX_HEADERS))).map(|r| r.path),
+            Ok("/teams".to_string())
+        );
+
+        let bad_length = read("POST /matches HTTP/1.1\r\nContent-Length: ten\r\n\r\n{}".to_string());
+        assert_eq!(bad_length, Err(BadRequest::Malformed("Content-Length must be a number")));
+        assert_eq!(bad_length.unwrap_err().response().status, 400);
+        assert_eq!(BadRequest::HeadersTooLarge.response().status, 431);
+    }
+}
@@ -0,0 +1,293 @@
+use std::collections::HashMap;
+
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+
+use crate::date::MatchDate;
+use crate::elo::EloRatings;
+use crate::fixtures::Fixture;
+use crate::league_table::{LeagueTable, TableRules};
+use crate::match_data::MatchData;
+use crate::predictor::{self, PoissonModel, MAX_SCORE};
+
+// Anything that can give scoreline probabilities for a fixture can drive
+// the simulator
+pub trait OutcomeModel {
+    // Probabilities indexed [home_goals][away_goals], summing to 1. Scores
+    // above 255 can't be recorded and are never drawn.
+    fn score_matrix(&self, home: &str, away: &str) -> Vec<Vec<f64>>;
+}
+
+impl OutcomeModel for PoissonModel {
+    fn score_matrix(&self, home: &str, away: &str) -> Vec<Vec<f64>> {
+        match self.predict(home, away) {
+            Some(prediction) => prediction.score_matrix,
+            // A team with no history plays like an average side
+            None => predictor::score_matrix(self.home_advantage(), 1.0, self.rho(), self.max_goals()),
+        }
+    }
+}
+
+// Turns Elo expected scores into goals: the stronger side gets the larger
+// share of an average number of goals per game
+#[derive(Debug, Clone)]
+pub struct EloScoreModel<'a> {
+    pub ratings: &'a EloRatings,
+    pub goals_per_game: f64,
+    pub max_goals: usize,       // Capped at 255
+}
+
+impl<'a> EloScoreModel<'a> {
+    pub fn new(ratings: &'a EloRatings) -> Self {
+        EloScoreModel {
+            ratings,
+            goals_per_game: 2.7,
+            max_goals: 10,
+        }
+    }
+}
+
+impl OutcomeModel for EloScoreModel<'_> {
+    fn score_matrix(&self, home: &str, away: &str) -> Vec<Vec<f64>> {
+        let expected = self.ratings.expected_score(home, away);
+        predictor::score_matrix(
+            self.goals_per_game * expected,
+            self.goals_per_game * (1.0 - expected),
+            0.0,
+            self.max_goals,
+        )
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct SimulationConfig {
+    pub iterations: usize,      // 0 gives an empty forecast
+    pub seed: u64,              // Same seed, same forecast
+    pub rules: TableRules,
+}
+
+impl Default for SimulationConfig {
+    fn default() -> Self {
+        SimulationConfig {
+            iterations: 10_000,
+            seed: 42,
+            rules: TableRules::default(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct SeasonForecast {
+    pub iterations: usize,
+    finishes: HashMap<String, Vec<usize>>,    // finishes[team][position - 1] = count
+}
+
+// With 0 iterations nothing is played and the forecast is empty
+pub fn simulate_season<M: OutcomeModel>(
+    played: &[MatchData],
+    remaining: &[Fixture],
+    model: &M,
+    config: &SimulationConfig,
+) -> SeasonForecast {
+    let mut rng = StdRng::seed_from_u64(config.seed);
+
+    // Scoreline distributions don't change between runs, so work out the
+    // cumulative probabilities once per fixture
+    let cumulative: Vec<Vec<(u8, u8, f64)>> = remaining
+        .iter()
+        .map(|f| cumulative_scores(&model.score_matrix(&f.home_team, &f.away_team)))
+        .collect();
+
+    // Simulated matches only feed the table, so they share the date of the
+    // last real match
+    let date = played
+        .last()
+        .map(|m| m.date)
+        .unwrap_or(MatchDate { year: 2000, month: 1, day: 1 });
+
+    let mut finishes: HashMap<String, Vec<usize>> = HashMap::new();
+    let mut season: Vec<MatchData> = played.to_vec();
+
+    for _ in 0..config.iterations {
+        season.truncate(played.len());
+        for (fixture, scores) in remaining.iter().zip(&cumulative) {
+            let (home_score, away_score) = sample(scores, rng.gen::<f64>());
+            season.push(fixture.result(date, home_score, away_score, Vec::new(), Vec::new()));
+        }
+
+        let table = LeagueTable::from_matches(&season, &config.rules);
+        let teams = table.rows().len();
+        for row in table.rows() {
+            let counts = finishes.entry(row.team.clone()).or_insert_with(|| vec![0; teams]);
+            counts.resize(counts.len().max(teams), 0);
+            counts[row.position - 1] += 1;
+        }
+    }
+
+    SeasonForecast {
+        iterations: config.iterations,
+        finishes,
+    }
+}
+
+impl SeasonForecast {
+    // Chance of finishing in each position, index 0 = first
+    pub fn probabilities(&self, team: &str) -> Vec<f64> {
+        self.finishes
+            .get(team)
+            .map(|counts| counts.iter().map(|c| *c as f64 / self.iterations as f64).collect())
+            .unwrap_or_default()
+    }
+
+    pub fn position_probability(&self, team: &str, position: usize) -> f64 {
+        if position == 0 {
+            return 0.0;
+        }
+        self.probabilities(team).get(position - 1).copied().unwrap_or(0.0)
+    }
+
+    pub fn title_probability(&self, team: &str) -> f64 {
+        self.position_probability(team, 1)
+    }
+
+    // e.g. Champions League places
+    pub fn top_probability(&self, team: &str, places: usize) -> f64 {
+        self.probabilities(team).iter().take(places).sum()
+    }
+
+    // e.g. relegation
+    pub fn bottom_probability(&self, team: &str, places: usize) -> f64 {
+        self.probabilities(team).iter().rev().take(places).sum()
+    }
+
+    pub fn average_position(&self, team: &str) -> f64 {
+        self.probabilities(team)
+            .iter()
+            .enumerate()
+            .map(|(i, p)| (i + 1) as f64 * p)
+            .sum()
+    }
+
+    // Teams ordered by average finishing position
+    pub fn teams(&self) -> Vec<String> {
+        let mut teams: Vec<String> = self.finishes.keys().cloned().collect();
+        teams.sort_by(|a, b| {
+            self.average_position(a)
+                .total_cmp(&self.average_position(b))
+                .then_with(|| a.cmp(b))
+        });
+        teams
+    }
+}
+
+fn cumulative_scores(matrix: &[Vec<f64>]) -> Vec<(u8, u8, f64)> {
+    let mut running = 0.0;
+    let mut cumulative = Vec::new();
+    for (h, row) in matrix.iter().enumerate().take(MAX_SCORE + 1) {
+        for (a, p) in row.iter().enumerate().take(MAX_SCORE + 1) {
+            running += p;
+            cumulative.push((h as u8, a as u8, running));
+        }
+    }
+    cumulative
+}
+
+fn sample(cumulative: &[(u8, u8, f64)], roll: f64) -> (u8, u8) {
+    let index = cumulative.partition_point(|(_, _, c)| *c <= roll);
+    cumulative
+        .get(index)
+        .or(cumulative.last())
+        .map(|(h, a, _)| (*h, *a))
+        .unwrap_or((0, 0))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::elo::EloConfig;
+    use crate::test_util::result;
+
+    // "Strong" wins every game 3-0, anyone else draws or wins 1-0 at home
+    struct OneDominantTeam;
+
+    impl OutcomeModel for OneDominantTeam {
+        fn score_matrix(&self, home: &str, away: &str) -> Vec<Vec<f64>> {
+            let mut matrix = vec![vec![0.0; 4]; 4];
+            if home == "Strong" {
+                matrix[3][0] = 1.0;
+            } else if away == "Strong" {
+                matrix[0][3] = 1.0;
+            } else {
+                matrix[0][0] = 0.5;
+                matrix[1][0] = 0.5;
+            }
+            matrix
+        }
+    }
+
+    fn fixtures() -> Vec<Fixture> {
+        let teams = ["Strong", "B", "C", "D"];
+        let mut fixtures = Vec::new();
+        for home in teams {
+            for away in teams.iter().filter(|a| **a != home) {
+                fixtures.push(Fixture {
+                    matchday: 1,
+                    home_team: home.to_string(),
+                    away_team: away.to_string(),
+                });
+            }
+        }
+        fixtures
+    }
+
+    fn config(seed: u64) -> SimulationConfig {
+        SimulationConfig {
+            iterations: 500,
+            seed,
+            ..SimulationConfig::default()
+        }
+    }
+
+    #[test]
+    fn test_same_seed_same_forecast() {
+        let played = vec![result("B", "C", 2, 1)];
+        let first = simulate_season(&played, &fixtures(), &OneDominantTeam, &config(7));
+        assert_eq!(first, simulate_season(&played, &fixtures(), &OneDominantTeam, &config(7)));
+        assert_ne!(first, simulate_season(&played, &fixtures(), &OneDominantTeam, &config(8)));
+    }
+
+    #[test]
+    fn test_probabilities_and_ordering() {
+        let forecast = simulate_season(&[], &fixtures(), &OneDominantTeam, &config(1));
+
+        for team in forecast.teams() {
+            let total: f64 = forecast.probabilities(&team).iter().sum();
+            assert!((total - 1.0).abs() < 1e-9, "{} sums to {}", team, total);
+        }
+        assert_eq!(forecast.title_probability("Strong"), 1.0);
+        assert_eq!(forecast.teams()[0], "Strong");
+        assert_eq!(forecast.bottom_probability("Strong", 3), 0.0);
+    }
+
+    #[test]
+    fn test_zero_iterations_gives_an_empty_forecast() {
+        let config = SimulationConfig { iterations: 0, ..SimulationConfig::default() };
+        let forecast = simulate_season(&[], &fixtures(), &OneDominantTeam, &config);
+        assert!(forecast.teams().is_empty());
+        assert!(forecast.probabilities("Strong").is_empty());
+        assert_eq!(forecast.title_probability("Strong"), 0.0);
+    }
+
+    #[test]
+    fn test_scores_never_wrap() {
+        let elo = EloRatings::new(EloConfig::default());
+        let model = EloScoreModel { max_goals: 300, goals_per_game: 600.0, ..EloScoreModel::new(&elo) };
+        let matrix = model.score_matrix("A", "B");
+        assert_eq!(matrix.len(), MAX_SCORE + 1);
+        assert!(matrix.iter().all(|row| row.len() == MAX_SCORE + 1));
+
+        // Any goals past 255 in a custom model are left out rather than wrapped
+        let cumulative = cumulative_scores(&vec![vec![0.5; 300]; 300]);
+        assert_eq!(cumulative.last().map(|(h, a, _)| (*h, *a)), Some((255, 255)));
+    }
+}
@@ -0,0 +1,233 @@
+use std::collections::HashMap;
+
+use serde::Serialize;
+
+use crate::match_data::MatchData;
+use crate::record::Record;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Venue {
+    Home,
+    Away,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct HomeAwaySplit {
+    pub team: String,
+    pub home: Record,
+    pub away: Record,
+}
+
+// League-wide numbers from the home side's point of view
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct HomeAdvantage {
+    pub matches: usize,
+    pub home_wins: usize,
+    pub draws: usize,
+    pub away_wins: usize,
+    pub home_goals: usize,
+    pub away_goals: usize,
+}
+
+impl HomeAwaySplit {
+    pub fn overall(&self) -> Record {
+        Record {
+            played: self.home.played + self.away.played,
+            won: self.home.won + self.away.won,
+            drawn: self.home.drawn + self.away.drawn,
+            lost: self.home.lost + self.away.lost,
+            goals_for: self.home.goals_for + self.away.goals_for,
+            goals_against: self.home.goals_against + self.away.goals_against,
+            clean_sheets: self.home.clean_sheets + self.away.clean_sheets,
+        }
+    }
+
+    pub fn record_at(&self, venue: Venue) -> Record {
+        match venue {
+            Venue::Home => self.home,
+            Venue::Away => self.away,
+        }
+    }
+
+    // How many more points per game the team takes at home
+    pub fn home_boost(&self) -> f64 {
+        self.home.points_per_game() - self.away.points_per_game()
+    }
+}
+
+impl HomeAdvantage {
+    pub fn home_win_rate(&self) -> f64 {
+        ratio(self.home_wins, self.matches)
+    }
+
+    pub fn draw_rate(&self) -> f64 {
+        ratio(self.draws, self.matches)
+    }
+
+    pub fn away_win_rate(&self) -> f64 {
+        ratio(self.away_wins, self.matches)
+    }
+
+    pub fn home_goals_per_game(&self) -> f64 {
+        ratio(self.home_goals, self.matches)
+    }
+
+    pub fn away_goals_per_game(&self) -> f64 {
+        ratio(self.away_goals, self.matches)
+    }
+
+    pub fn home_points_per_game(&self) -> f64 {
+        ratio(self.home_wins * 3 + self.draws, self.matches)
+    }
+
+    pub fn away_points_per_game(&self) -> f64 {
+        ratio(self.away_wins * 3 + self.draws, self.matches)
+    }
+
+    // Extra points per game earned by playing at home; 0.0 means no advantage
+    pub fn points_advantage(&self) -> f64 {
+        self.home_points_per_game() - self.away_points_per_game()
+    }
+}
+
+// One split per team, sorted by team name
+pub fn home_away_splits<'a, I>(matches: I) -> Vec<HomeAwaySplit>
+where
+    I: IntoIterator<Item = &'a MatchData>,
+{
+    let mut splits: HashMap<String, HomeAwaySplit> = HashMap::new();
+
+    for m in matches {
+        splits
+            .entry(m.home_team.clone())
+            .or_insert_with(|| empty_split(&m.home_team))
+            .home
+            .add(m.home_score, m.away_score);
+        splits
+            .entry(m.away_team.clone())
+            .or_insert_with(|| empty_split(&m.away_team))
+            .away
+            .add(m.away_score, m.home_score);
+    }
+
+    let mut splits: Vec<HomeAwaySplit> = splits.into_values().collect();
+    splits.sort_by(|a, b| a.team.cmp(&b.team));
+    splits
+}
+
+pub fn home_advantage<'a, I>(matches: I) -> HomeAdvantage
+where
+    I: IntoIterator<Item = &'a MatchData>,
+{
+    let mut advantage = HomeAdvantage::default();
+
+    for m in matches {
+        advantage.matches += 1;
+        advantage.home_goals += m.home_score as usize;
+        advantage.away_goals += m.away_score as usize;
+        if m.home_score > m.away_score {
+            advantage.home_wins += 1;
+        } else if m.home_score == m.away_score {
+            advantage.draws += 1;
+        } else {
+            advantage.away_wins += 1;
+        }
+    }
+
+    advantage
+}
+
+// Home-only or away-only standings, best points per game first
+pub fn venue_table(splits: &[HomeAwaySplit], venue: Venue) -> Vec<(String, Record)> {
+    let mut table: Vec<(String, Record)> = splits
+        .iter()
+        .map(|s| (s.team.clone(), s.record_at(venue)))
+        .filter(|(_, record)| record.played > 0)
+        .collect();
+
+    table.sort_by(|a, b| {
+        b.1.points_per_game()
+            .total_cmp(&a.1.points_per_game())
+            .then_with(|| b.1.goal_difference().cmp(&a.1.goal_difference()))
+            .then_with(|| a.0.cmp(&b.0))
+    });
+    table
+}
+
+fn empty_split(team: &str) -> HomeAwaySplit {
+    HomeAwaySplit {
+        team: team.to_string(),
+        home: Record::default(),
+        away: Record::default(),
+    }
+}
+
+fn ratio(count: usize, total: usize) -> f64 {
+    if total == 0 {
+        0.0
+    } else {
+        count as f64 / total as f64
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::result;
+
+    fn matches() -> Vec<MatchData> {
+        vec![
+            result("Arsenal", "Chelsea", 2, 0),
+            result("Chelsea", "Arsenal", 1, 1),
+            result("Arsenal", "Spurs", 1, 0),
+            result("Spurs", "Chelsea", 3, 1),
+            result("Chelsea", "Spurs", 0, 2),
+        ]
+    }
+
+    #[test]
+    fn test_home_away_splits() {
+        let splits = home_away_splits(&matches());
+        let teams: Vec<&str> = splits.iter().map(|s| s.team.as_str()).collect();
+        assert_eq!(teams, vec!["Arsenal", "Chelsea", "Spurs"]);
+
+        let arsenal = &splits[0];
+        assert_eq!(arsenal.home.points(3, 1), 6);
+        assert_eq!((arsenal.home.goals_for, arsenal.home.goals_against, arsenal.home.clean_sheets), (3, 0, 2));
+        assert_eq!(arsenal.away.points(3, 1), 1);
+        assert_eq!((arsenal.away.goals_for, arsenal.away.goals_against, arsenal.away.clean_sheets), (1, 1, 0));
+        assert_eq!(arsenal.overall().played, 3);
+        assert_eq!(arsenal.overall().clean_sheets, 2);
+        assert_eq!(arsenal.home_boost(), 3.0 - 1.0);
+
+        let chelsea = &splits[1];
+        assert_eq!(chelsea.record_at(Venue::Home).points(3, 1), 1);
+        assert_eq!(chelsea.record_at(Venue::Away).points(3, 1), 0);
+        assert_eq!(chelsea.record_at(Venue::Away).goals_against, 5);
+    }
+
+    #[test]
+    fn test_home_advantage() {
+        let none = home_advantage(&[]);
+        assert_eq!(none, HomeAdvantage::default());
+        assert_eq!(none.home_win_rate(), 0.0);
+        assert_eq!(none.points_advantage(), 0.0);
+
+        let advantage = home_advantage(&matches());
+        assert_eq!((advantage.home_wins, advantage.draws, advantage.away_wins), (3, 1, 1));
+        assert_eq!((advantage.home_goals, advantage.away_goals), (7, 4));
+        assert_eq!(advantage.home_win_rate(), 0.6);
+        assert!((advantage.points_advantage() - (10.0 - 4.0) / 5.0).abs() < 1e-12);
+    }
+
+    #[test]
+    fn test_venue_table_order() {
+        let splits = home_away_splits(&matches());
+        let home: Vec<String> = venue_table(&splits, Venue::Home).into_iter().map(|(team, _)| team).collect();
+        // Arsenal and Spurs win every home game; Arsenal has the better goal difference
+        assert_eq!(home, vec!["Arsenal", "Spurs", "Chelsea"]);
+
+        let away: Vec<String> = venue_table(&splits, Venue::Away).into_iter().map(|(team, _)| team).collect();
+        assert_eq!(away, vec!["Spurs", "Arsenal", "Chelsea"]);
+    }
+}
@@ -0,0 +1,202 @@
+use std::fmt;
+
+use serde::{Deserialize, Serialize};
+
+use crate::player::{Player, Position};
+
+pub const MAX_RATING: u8 = 100;
+
+// Registration limits; competitions differ, so every team carries its own
+// and a squad can also be checked against another competition's rules
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SquadRules {
+    pub max_squad_size: Option<usize>,
+    pub min_goalkeepers: usize,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum SquadError {
+    DuplicateJersey { jersey_number: u8, existing: String },
+    InvalidRating { player: String, rating: u8 },
+    SquadFull { max_squad_size: usize },
+    TooFewGoalkeepers { required: usize, found: usize },
+}
+
+impl Default for SquadRules {
+    fn default() -> Self {
+        SquadRules {
+            max_squad_size: None,
+            min_goalkeepers: 1,
+        }
+    }
+}
+
+impl SquadRules {
+    pub fn new(max_squad_size: Option<usize>, min_goalkeepers: usize) -> Self {
+        SquadRules {
+            max_squad_size,
+            min_goalkeepers,
+        }
+    }
+
+    pub fn premier_league() -> Self {
+        SquadRules::new(Some(25), 1)
+    }
+
+    // UEFA list A: 25 players, two of them goalkeepers
+    pub fn uefa() -> Self {
+        SquadRules::new(Some(25), 2)
+    }
+
+    // Whether `player` can join `squad` as it stands
+    pub fn check_new_player(&self, squad: &[Player], player: &Player) -> Result<(), SquadError> {
+        check_rating(player)?;
+        if let Some(existing) = squad.iter().find(|p| p.jersey_number == player.jersey_number) {
+            return Err(SquadError::DuplicateJersey {
+                jersey_number: player.jersey_number,
+                existing: existing.name.clone(),
+            });
+        }
+        match self.max_squad_size {
+            Some(max) if squad.len() >= max => Err(SquadError::SquadFull { max_squad_size: max }),
+            _ => Ok(()),
+        }
+    }
+
+    // Everything wrong with a complete squad; empty when it's valid
+    pub fn validate(&self, squad: &[Player]) -> Vec<SquadError> {
+        let mut errors = Vec::new();
+
+        for (i, player) in squad.iter().enumerate() {
+            if let Err(e) = check_rating(player) {
+                errors.push(e);
+            }
+            if let Some(existing) = squad[..i].iter().find(|p| p.jersey_number == player.jersey_number) {
+                errors.push(SquadError::DuplicateJersey {
+                    jersey_number: player.jersey_number,
+                    existing: existing.name.clone(),
+                });
+            }
+        }
+
+        if let Some(max) = self.max_squad_size {
+            if squad.len() > max {
+                errors.push(SquadError::SquadFull { max_squad_size: max });
+            }
+        }
+
+        let goalkeepers = squad.iter().filter(|p| p.position == Position::Goalkeeper).count();
+        if goalkeepers < self.min_goalkeepers {
+            errors.push(SquadError::TooFewGoalkeepers {
+                required: self.min_goalkeepers,
+                found: goalkeepers,
+            });
+        }
+
+        errors
+    }
+}
+
+fn check_rating(player: &Player) -> Result<(), SquadError> {
+    if player.rating > MAX_RATING {
+        Err(SquadError::InvalidRating {
+            player: player.name.clone(),
+            rating: player.rating,
+        })
+    } else {
+        Ok(())
+    }
+}
+
+impl fmt::Display for SquadError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SquadError::DuplicateJersey { jersey_number, existing } => {
+                write!(f, "#{} is already worn by {}", jersey_number, existing)
+            }
+            SquadError::InvalidRating { player, rating } => {
+                write!(f, "{} has rating {}, ratings go from 0 to {}", player, rating, MAX_RATING)
+            }
+            SquadError::SquadFull { max_squad_size } => write!(f, "squad is full ({} players)", max_squad_size),
+            SquadError::TooFewGoalkeepers { required, found } => {
+                write!(f, "squad needs {} goalkeeper(s) but has {}", required, found)
+            }
+        }
+    }
+}
+
+impl std::error::Error for SquadError {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::team::Team;
+    use crate::test_util::player;
+
+    #[test]
+    fn test_new_player_checks() {
+        let rules = SquadRules::new(Some(2), 1);
+        let squad = vec![player("Keeper", 1, Position::Goalkeeper, 80)];
+
+        assert_eq!(
+            rules.check_new_player(&squad, &player("Other Keeper", 1, Position::Goalkeeper, 70)),
+            Err(SquadError::DuplicateJersey { jersey_number: 1, existing: "Keeper".to_string() })
+        );
+        assert!(matches!(
+            rules.check_new_player(&squad, &player("Superstar", 10, Position::Forward, 101)),
+            Err(SquadError::InvalidRating { rating: 101, .. })
+        ));
+        assert_eq!(rules.check_new_player(&squad, &player("Striker", 9, Position::Forward, 85)), Ok(()));
+
+        let full = vec![squad[0].clone(), player("Striker", 9, Position::Forward, 85)];
+        assert_eq!(
+            rules.check_new_player(&full, &player("Winger", 7, Position::Forward, 80)),
+            Err(SquadError::SquadFull { max_squad_size: 2 })
+        );
+    }
+
+    #[test]
+    fn test_validate_against_competition_rules() {
+        let squad = vec![
+            player("Keeper", 1, Position::Goalkeeper, 80),
+            player("Striker", 9, Position::Forward, 85),
+        ];
+        assert!(SquadRules::premier_league().validate(&squad).is_empty());
+        assert_eq!(
+            SquadRules::uefa().validate(&squad),
+            vec![SquadError::TooFewGoalkeepers { required: 2, found: 1 }]
+        );
+    }
+
+    #[test]
+    fn test_team_changes_go_through_the_rules() {
+        let mut team = Team::new("Ajax".to_string(), "Amsterdam".to_string()).with_rules(SquadRules::new(Some(1), 0));
+        team.add_player(player("Keeper", 1, Position::Goalkeeper, 80)).unwrap();
+        assert!(team.add_player(player("Striker", 9, Position::Forward, 85)).is_err());
+        assert_eq!(team.players().len(), 1);
+
+        assert_eq!(team.set_rating(1, 84), Ok(true));
+        assert_eq!(team.set_rating(9, 84), Ok(false));
+        assert!(matches!(team.set_rating(1, 101), Err(SquadError::InvalidRating { rating: 101, .. })));
+        assert_eq!(team.players()[0].rating, 84);
+    }
+
+    #[test]
+    fn test_deserialized_squads_are_checked() {
+        let json = |players: &str| format!(r#"{{"name": "X", "city": "Y", "players": [{}]}}"#, players);
+        let keeper = r#"{"name": "Keeper", "jersey_number": 1, "position": "Goalkeeper", "rating": 80}"#;
+        let also_one = r#"{"name": "Impostor", "jersey_number": 1, "position": "Forward", "rating": 70}"#;
+        let overrated = r#"{"name": "Superstar", "jersey_number": 10, "position": "Forward", "rating": 250}"#;
+        let striker = r#"{"name": "Striker", "jersey_number": 9, "position": "Forward", "rating": 85}"#;
+
+        let err = serde_json::from_str::<Team>(&json(&format!("{}, {}", keeper, also_one))).unwrap_err();
+        assert!(err.to_string().contains("#1 is already worn by Keeper"), "{}", err);
+        let err = serde_json::from_str::<Team>(&json(overrated)).unwrap_err();
+        assert!(err.to_string().contains("Superstar has rating 250"), "{}", err);
+
+        // The goalkeeper minimum is only reported by validate
+        let team: Team = serde_json::from_str(&json(striker)).unwrap();
+        assert_eq!(team.players().len(), 1);
+        assert_eq!(team.validate(), vec![SquadError::TooFewGoalkeepers { required: 1, found: 0 }]);
+    }
+}
@@ -0,0 +1,463 @@
+use std::fmt;
+use std::path::Path;
+
+use rusqlite::{params, Connection, OptionalExtension, Params, Row};
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+
+use crate::analytics::FootballAnalytics;
+use crate::competition::{Competition, Scope, Season};
+use crate::date::{DateRange, KickoffTime, MatchDate};
+use crate::match_data::MatchData;
+use crate::player::Player;
+use crate::team::Team;
+
+// Embedded SQLite storage for teams, players and matches. Scores, teams and
+// dates get their own columns so they can be filtered in SQL; event lists
+// (goals, shots, lineups, cards) are stored as JSON.
+//
+// The schema version lives in `PRAGMA user_version`. Opening a file runs
+// every migration it hasn't seen yet, so add new ones to the end of
+// `MIGRATIONS` and never edit old ones.
+
+const MIGRATIONS: &[&str] = &[
+    // 1: initial schema
+    "CREATE TABLE teams (
+         id INTEGER PRIMARY KEY,
+         name TEXT NOT NULL UNIQUE,
+         city TEXT NOT NULL,
+         rules TEXT NOT NULL
+     );
+     CREATE TABLE players (
+         id INTEGER PRIMARY KEY,
+         team_id INTEGER NOT NULL REFERENCES teams(id) ON DELETE CASCADE,
+         name TEXT NOT NULL,
+         jersey_number INTEGER NOT NULL,
+         position TEXT NOT NULL,
+         rating INTEGER NOT NULL,
+         UNIQUE (team_id, jersey_number)
+     );
+     CREATE TABLE matches (
+         id INTEGER PRIMARY KEY,
+         date TEXT NOT NULL,
+         kickoff TEXT,
+         home_team TEXT NOT NULL,
+         away_team TEXT NOT NULL,
+         home_score INTEGER NOT NULL,
+         away_score INTEGER NOT NULL,
+         home_goals TEXT NOT NULL,
+         away_goals TEXT NOT NULL,
+         home_shots TEXT NOT NULL,
+         away_shots TEXT NOT NULL,
+         home_lineup TEXT NOT NULL,
+         away_lineup TEXT NOT NULL,
+         home_cards TEXT NOT NULL,
+         away_cards TEXT NOT NULL
+     );
+     CREATE INDEX matches_by_date ON matches (date, kickoff);",
+    // 2: competitions and seasons
+    "CREATE TABLE competitions (
+         name TEXT PRIMARY KEY,
+         kind TEXT NOT NULL,
+         country TEXT
+     );
+     ALTER TABLE matches ADD COLUMN competition TEXT;
+     ALTER TABLE matches ADD COLUMN season INTEGER;
+     CREATE INDEX matches_by_competition ON matches (competition, date);",
+];
+
+const MATCH_COLUMNS: &str = "date, kickoff, home_team, away_team, home_score, away_score,
+                             home_goals, away_goals, home_shots, away_shots,
+                             home_lineup, away_lineup, home_cards, away_cards,
+                             competition, season";
+
+#[derive(Debug)]
+pub enum StoreError {
+    Sqlite(rusqlite::Error),
+    Json(serde_json::Error),
+    BadValue(String),           // A column that doesn't parse back into the model
+    NewerSchema(u32),           // File written by a newer version of this crate
+}
+
+pub struct SqliteStore {
+    conn: Connection,
+}
+
+impl SqliteStore {
+    pub fn open<P: AsRef<Path>>(path: P) -> Result<Self, StoreError> {
+        SqliteStore::from_connection(Connection::open(path)?)
+    }
+
+    pub fn open_in_memory() -> Result<Self, StoreError> {
+        SqliteStore::from_connection(Connection::open_in_memory()?)
+    }
+
+    fn from_connection(mut conn: Connection) -> Result<Self, StoreError> {
+        conn.execute_batch("PRAGMA foreign_keys = ON;")?;
+        migrate(&mut conn)?;
+        Ok(SqliteStore { conn })
+    }
+
+    pub fn schema_version(&self) -> Result<u32, StoreError> {
+        Ok(self.conn.pragma_query_value(None, "user_version", |row| row.get(0))?)
+    }
+
+    // Inserts the team or replaces its city, rules and whole squad
+    pub fn save_team(&mut self, team: &Team) -> Result<(), StoreError> {
+        let tx = self.conn.transaction()?;
+        tx.execute(
+            "INSERT INTO teams (name, city, rules) VALUES (?1, ?2, ?3)
+             ON CONFLICT (name) DO UPDATE SET city = excluded.city, rules = excluded.rules",
+            params![team.name, team.city, to_json(&team.rules)?],
+        )?;
+        let team_id: i64 = tx.query_row("SELECT id FROM teams WHERE name = ?1", [&team.name], |row| row.get(0))?;
+
+        tx.execute("DELETE FROM players WHERE team_id = ?1", [team_id])?;
+        for player in team.players() {
+            tx.execute(
+                "INSERT INTO players (team_id, name, jersey_number, position, rating) VALUES (?1, ?2, ?3, ?4, ?5)",
+                params![team_id, player.name, player.jersey_number, to_json(&player.position)?, player.rating],
+            )?;
+        }
+        tx.commit()?;
+        Ok(())
+    }
+
+    pub fn team(&self, name: &str) -> Result<Option<Team>, StoreError> {
+        let row = self
+            .conn
+            .query_row("SELECT id, name, city, rules FROM teams WHERE name = ?1", [name], |row| {
+                Ok((row.get::<_, i64>(0)?, row.get(1)?, row.get(2)?, row.get::<_, String>(3)?))
+            })
+            .optional()?;
+
+        match row {
+            Some((id, name, city, rules)) => Ok(Some(self.load_team(id, name, city, &rules)?)),
+            None => Ok(None),
+        }
+    }
+
+    // Sorted by name
+    pub fn teams(&self) -> Result<Vec<Team>, StoreError> {
+        let mut stmt = self.conn.prepare("SELECT id, name, city, rules FROM teams ORDER BY name")?;
+        let rows = stmt
+            .query_map([], |row| Ok((row.get::<_, i64>(0)?, row.get(1)?, row.get(2)?, row.get::<_, String>(3)?)))?
+            .collect::<Result<Vec<(i64, String, String, String)>, _>>()?;
+
+        rows.into_iter()
+            .map(|(id, name, city, rules)| self.load_team(id, name, city, &rules))
+            .collect()
+    }
+
+    pub fn add_match(&mut self, m: &MatchData) -> Result<(), StoreError> {
+        insert_match(&self.conn, m)
+    }
+
+    // All or nothing
+    pub fn add_matches(&mut self, matches: &[MatchData]) -> Result<(), StoreError> {
+        let tx = self.conn.transaction()?;
+        for m in matches {
+            insert_match(&tx, m)?;
+        }
+        tx.commit()?;
+        Ok(())
+    }
+
+    // Oldest first. Dates are stored as ISO strings, so the range filter and
+    // ordering both happen in SQL.
+    pub fn matches_in(&self, range: &DateRange) -> Result<Vec<MatchData>, StoreError> {
+        let from = range.from.map(|d| d.to_string());
+        let to = range.to.map(|d| d.to_string());
+        self.select_matches("(?1 IS NULL OR date >= ?1) AND (?2 IS NULL OR date <= ?2)", params![from, to])
+    }
+
+    // The competition is filtered in SQL; seasons fall back to the match date
+    // when untagged, so they are checked afterwards
+    pub fn matches_for(&self, scope: &Scope) -> Result<Vec<MatchData>, StoreError> {
+        let matches = self.select_matches("(?1 IS NULL OR competition = ?1)", params![scope.competition])?;
+        Ok(matches.into_iter().filter(|m| scope.contains(m)).collect())
+    }
+
+    fn select_matches<P: Params>(&self, filter: &str, params: P) -> Result<Vec<MatchData>, StoreError> {
+        let mut stmt = self.conn.prepare(&format!(
+            "SELECT {} FROM matches WHERE {} ORDER BY date, kickoff, id",
+            MATCH_COLUMNS, filter
+        ))?;
+        let mut rows = stmt.query(params)?;
+
+        let mut matches = Vec::new();
+        while let Some(row) = rows.next()? {
+            matches.push(match_from_row(row)?);
+        }
+        Ok(matches)
+    }
+
+    pub fn save_competition(&self, competition: &Competition) -> Result<(), StoreError> {
+        self.conn.execute(
+            "INSERT INTO competitions (name, kind, country) VALUES (?1, ?2, ?3)
+             ON CONFLICT (name) DO UPDATE SET kind = excluded.kind, country = excluded.country",
+            params![competition.name, to_json(&competition.kind)?, competition.country],
+        )?;
+        Ok(())
+    }
+
+    pub fn competitions(&self) -> Result<Vec<Competition>, StoreError> {
+        let mut stmt = self.conn.prepare("SELECT name, kind, country FROM competitions ORDER BY name")?;
+        let rows = stmt
+            .query_map([], |row| Ok((row.get::<_, String>(0)?, row.get::<_, String>(1)?, row.get(2)?)))?
+            .collect::<Result<Vec<(String, String, Option<String>)>, _>>()?;
+        rows.into_iter()
+            .map(|(name, kind, country)| Ok(Competition::new(name, from_json(&kind)?, country)))
+            .collect()
+    }
+
+    pub fn match_count(&self) -> Result<usize, StoreError> {
+        let count: i64 = self.conn.query_row("SELECT COUNT(*) FROM matches", [], |row| row.get(0))?;
+        Ok(count as usize)
+    }
+
+    // Loads the stored matches so every `FootballAnalytics` method can run
+    // on them; pass a range to keep big archives out of memory
+    pub fn analytics(&self) -> Result<FootballAnalytics, StoreError> {
+        self.analytics_in(&DateRange::all())
+    }
+
+    pub fn analytics_in(&self, range: &DateRange) -> Result<FootballAnalytics, StoreError> {
+        self.analytics_from(self.matches_in(range)?)
+    }
+
+    pub fn analytics_for(&self, scope: &Scope) -> Result<FootballAnalytics, StoreError> {
+        self.analytics_from(self.matches_for(scope)?)
+    }
+
+    fn analytics_from(&self, matches: Vec<MatchData>) -> Result<FootballAnalytics, StoreError> {
+        let mut analytics = FootballAnalytics::new();
+        for competition in self.competitions()? {
+            analytics.add_competition(competition);
+        }
+        for m in matches {
+            analytics.add_match(m);
+        }
+        Ok(analytics)
+    }
+
+    fn load_team(&self, id: i64, name: String, city: String, rules: &str) -> Result<Team, StoreError> {
+        let rules = from_json(rules)?;
+
+        let mut stmt = self
+            .conn
+            .prepare("SELECT name, jersey_number, position, rating FROM players WHERE team_id = ?1 ORDER BY id")?;
+        let rows = stmt
+            .query_map([id], |row| {
+                Ok((row.get::<_, String>(0)?, row.get(1)?, row.get::<_, String>(2)?, row.get(3)?))
+            })?
+            .collect::<Result<Vec<(String, u8, String, u8)>, _>>()?;
+        let players = rows
+            .into_iter()
+            .map(|(name, jersey_number, position, rating)| {
+                Ok(Player::new(name, jersey_number, from_json(&position)?, rating))
+            })
+            .collect::<Result<Vec<Player>, StoreError>>()?;
+        // Stored squads were valid when saved, so they skip add_player's checks
+        Ok(Team::new(name, city).with_rules(rules).with_players_unchecked(players))
+    }
+}
+
+// Runs the migrations the file hasn't seen yet, each in its own transaction
+fn migrate(conn: &mut Connection) -> Result<(), StoreError> {
+    let current: u32 = conn.pragma_query_value(None, "user_version", |row| row.get(0))?;
+    if current as usize > MIGRATIONS.len() {
+        return Err(StoreError::NewerSchema(current));
+    }
+
+    for (i, sql) in MIGRATIONS.iter().enumerate().skip(current as usize) {
+        let tx = conn.transaction()?;
+        tx.execute_batch(sql)?;
+        tx.pragma_update(None, "user_version", (i + 1) as u32)?;
+        tx.commit()?;
+    }
+    Ok(())
+}
+
+fn insert_match(conn: &Connection, m: &MatchData) -> Result<(), StoreError> {
+    conn.execute(
+        "INSERT INTO matches (date, kickoff, home_team, away_team, home_score, away_score,
+                              home_goals, away_goals, home_shots, away_shots,
+                              home_lineup, away_lineup, home_cards, away_cards,
+                              competition, season)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14, ?15, ?16)",
+        params![
+            m.date.to_string(),
+            m.kickoff.map(|k| k.to_string()),
+            m.home_team,
+            m.away_team,
+            m.home_score,
+            m.away_score,
+            to_json(&m.home_goals)?,
+            to_json(&m.away_goals)?,
+            to_json(&m.home_shots)?,
+            to_json(&m.away_shots)?,
+            to_json(&m.home_lineup)?,
+            to_json(&m.away_lineup)?,
+            to_json(&m.home_cards)?,
+            to_json(&m.away_cards)?,
+            m.competition,
+            m.season.map(|s| s.start_year()),
+        ],
+    )?;
+    Ok(())
+}
+
+fn match_from_row(row: &Row) -> Result<MatchData, StoreError> {
+    let date: String = row.get(0)?;
+    let kickoff: Option<String> = row.get(1)?;
+
+    let mut m = MatchData::new(
+        MatchDate::parse(&date).ok_or_else(|| StoreError::BadValue(format!("date '{}'", date)))?,
+        row.get(2)?,
+        row.get(3)?,
+        row.get(4)?,
+        row.get(5)?,
+        from_json(&row.get::<_, String>(6)?)?,
+        from_json(&row.get::<_, String>(7)?)?,
+    );
+    if let Some(kickoff) = kickoff {
+        m.kickoff = Some(KickoffTime::parse(&kickoff).ok_or_else(|| StoreError::BadValue(format!("kickoff '{}'", kickoff)))?);
+    }
+    m.home_shots = from_json(&row.get::<_, String>(8)?)?;
+    m.away_shots = from_json(&row.get::<_, String>(9)?)?;
+    m.home_lineup = from_json(&row.get::<_, String>(10)?)?;
+    m.away_lineup = from_json(&row.get::<_, String>(11)?)?;
+    m.home_cards = from_json(&row.get::<_, String>(12)?)?;
+    m.away_cards = from_json(&row.get::<_, String>(13)?)?;
+    m.competition = row.get(14)?;
+    if let Some(year) = row.get::<_, Option<u16>>(15)? {
+        m.season = Some(Season::new(year).ok_or_else(|| StoreError::BadValue(format!("season {}", year)))?);
+    }
+    Ok(m)
+}
+
+fn to_json<T: Serialize>(value: &T) -> Result<String, StoreError> {
+    Ok(serde_json::to_string(value)?)
+}
+
+fn from_json<T: DeserializeOwned>(json: &str) -> Result<T, StoreError> {
+    Ok(serde_json::from_str(json)?)
+}
+
+impl fmt::Display for StoreError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            StoreError::Sqlite(e) => write!(f, "database error: {}", e),
+            StoreError::Json(e) => write!(f, "invalid stored JSON: {}", e),
+            StoreError::BadValue(what) => write!(f, "invalid stored {}", what),
+            StoreError::NewerSchema(v) => {
+                write!(f, "database schema version {} is newer than the supported {}", v, MIGRATIONS.len())
+            }
+        }
+    }
+}
+
+impl std::error::Error for StoreError {}
+
+impl From<rusqlite::Error> for StoreError {
+    fn from(e: rusqlite::Error) -> Self {
+        StoreError::Sqlite(e)
+    }
+}
+
+impl From<serde_json::Error> for StoreError {
+    fn from(e: serde_json::Error) -> Self {
+        StoreError::Json(e)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::competition::CompetitionKind;
+    use crate::events::{Goal, GoalKind};
+    use crate::lineup::Lineup;
+    use crate::player::Position;
+    use crate::test_util::{date, player, result_on};
+
+    #[test]
+    fn test_teams_and_matches_round_trip() {
+        let mut store = SqliteStore::open_in_memory().unwrap();
+        assert_eq!(store.schema_version().unwrap() as usize, MIGRATIONS.len());
+
+        let mut team = Team::new("FC Barcelona".to_string(), "Barcelona".to_string());
+        team.add_player(player("Lionel Messi", 10, Position::Forward, 91)).unwrap();
+        store.save_team(&team).unwrap();
+        team.add_player(player("Pedri", 8, Position::Midfielder, 86)).unwrap();
+        store.save_team(&team).unwrap();
+
+        let loaded = store.team("FC Barcelona").unwrap().unwrap();
+        assert_eq!(loaded.players(), team.players());
+        assert!(store.team("Real Madrid").unwrap().is_none());
+
+        let scored = result_on(date(2024, 4, 21), "Real Madrid", "FC Barcelona", 0, 1);
+        let scored = MatchData {
+            away_goals: vec![Goal::new("Messi".to_string(), 45, GoalKind::Penalty)],
+            ..scored
+        };
+        store
+            .add_matches(&[scored.clone(), result_on(date(2023, 10, 28), "FC Barcelona", "Real Madrid", 2, 2)])
+            .unwrap();
+
+        let season = store.matches_in(&DateRange::season(2023).unwrap()).unwrap();
+        assert_eq!(season.len(), 2);
+        assert_eq!(season[1], scored);
+        assert_eq!(store.matches_in(&DateRange::since(date(2024, 1, 1))).unwrap().len(), 1);
+        assert_eq!(store.analytics().unwrap().get_top_scorers(1), vec![("Messi".to_string(), 1)]);
+    }
+
+    #[test]
+    fn test_competition_scope() {
+        let mut store = SqliteStore::open_in_memory().unwrap();
+        store
+            .save_competition(&Competition::new("Copa del Rey".to_string(), CompetitionKind::Cup, Some("Spain".to_string())))
+            .unwrap();
+        let cup = result_on(date(2024, 2, 6), "Athletic Club", "Atlético Madrid", 1, 0)
+            .with_competition("Copa del Rey", None);
+        store
+            .add_matches(&[cup.clone(), result_on(date(2024, 2, 3), "Real Madrid", "Atlético Madrid", 1, 1)])
+            .unwrap();
+
+        assert_eq!(store.matches_for(&Scope::competition("Copa del Rey")).unwrap(), vec![cup]);
+        assert_eq!(store.matches_for(&Scope::season(Season::new(2023).unwrap())).unwrap().len(), 2);
+        let analytics = store.analytics_for(&Scope::competition("Copa del Rey")).unwrap();
+        assert_eq!(analytics.competition("Copa del Rey").unwrap().kind, CompetitionKind::Cup);
+    }
+
+    #[test]
+    fn test_version_1_files_are_upgraded() {
+        let conn = Connection::open_in_memory().unwrap();
+        conn.execute_batch(MIGRATIONS[0]).unwrap();
+        conn.pragma_update(None, "user_version", 1).unwrap();
+        conn.execute(
+            "INSERT INTO matches (date, kickoff, home_team, away_team, home_score, away_score,
+                                  home_goals, away_goals, home_shots, away_shots,
+                                  home_lineup, away_lineup, home_cards, away_cards)
+             VALUES ('2023-10-28', NULL, 'FC Barcelona', 'Real Madrid', 2, 2, '[]', '[]', '[]', '[]', ?1, ?1, '[]', '[]')",
+            [to_json(&Lineup::default()).unwrap()],
+        )
+        .unwrap();
+
+        let store = SqliteStore::from_connection(conn).unwrap();
+        assert_eq!(store.schema_version().unwrap(), 2);
+        let matches = store.matches_in(&DateRange::all()).unwrap();
+        assert_eq!(matches, vec![result_on(date(2023, 10, 28), "FC Barcelona", "Real Madrid", 2, 2)]);
+        assert_eq!((matches[0].competition.clone(), matches[0].season), (None, None));
+    }
+
+    #[test]
+    fn test_newer_schema_is_rejected() {
+        let conn = Connection::open_in_memory().unwrap();
+        conn.pragma_update(None, "user_version", MIGRATIONS.len() as u32 + 1).unwrap();
+        assert!(matches!(
+            SqliteStore::from_connection(conn),
+            Err(StoreError::NewerSchema(v)) if v as usize == MIGRATIONS.len() + 1
+        ));
+    }
+}
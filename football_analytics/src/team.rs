@@ -1,10 +1,43 @@
+use serde::{Deserialize, Serialize};
+
+use crate::formation::{Formation, FormationError, StartingXi};
 use crate::player::{Player, Position};
+use crate::squad::{SquadError, SquadRules, MAX_RATING};
 
-#[derive(Debug)]
+// Deserializing goes through `add_player`, so a stored squad has to pass
+// the same checks as one built in code
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(try_from = "TeamData")]
 pub struct Team {
     pub name: String,
     pub city: String,
-    pub players: Vec<Player>,    // Vector storing list of players
+    players: Vec<Player>,        // Only changed through the checked methods below
+    #[serde(default)]
+    pub rules: SquadRules,       // Limits enforced by add_player
+}
+
+// A team as written in JSON, before its squad has been checked
+#[derive(Debug, Clone, Deserialize)]
+pub(crate) struct TeamData {
+    pub name: String,
+    pub city: String,
+    pub players: Vec<Player>,
+    #[serde(default)]
+    pub rules: SquadRules,
+}
+
+// Only the goalkeeper minimum is left unchecked, as it is when building a
+// squad one player at a time
+impl TryFrom<TeamData> for Team {
+    type Error = SquadError;
+
+    fn try_from(data: TeamData) -> Result<Self, SquadError> {
+        let mut team = Team::new(data.name, data.city).with_rules(data.rules);
+        for player in data.players {
+            team.add_player(player)?;
+        }
+        Ok(team)
+    }
 }
 
 impl Team {
@@ -13,11 +46,62 @@ impl Team {
             name,
             city,
             players: Vec::new(),     // Initialize empty vector
+            rules: SquadRules::default(),
         }
     }
     
-    pub fn add_player(&mut self, player: Player) {
+    pub fn with_rules(mut self, rules: SquadRules) -> Self {
+        self.rules = rules;
+        self
+    }
+    
+    // Skips add_player's checks. Only for squads that were valid when they
+    // were recorded, such as stored or past squads.
+    pub(crate) fn with_players_unchecked(mut self, players: Vec<Player>) -> Self {
+        self.players = players;
+        self
+    }
+    
+    pub fn players(&self) -> &[Player] {
+        &self.players
+    }
+    
+    // Rejects duplicate jersey numbers, ratings above 100 and players
+    // beyond the maximum squad size
+    pub fn add_player(&mut self, player: Player) -> Result<(), SquadError> {
+        self.can_add_player(&player)?;
         self.players.push(player);
+        Ok(())
+    }
+    
+    pub fn can_add_player(&self, player: &Player) -> Result<(), SquadError> {
+        self.rules.check_new_player(&self.players, player)
+    }
+    
+    // Every rule the squad breaks under its own rules, including the
+    // goalkeeper minimum that add_player can't enforce one player at a time
+    pub fn validate(&self) -> Vec<SquadError> {
+        self.rules.validate(&self.players)
+    }
+    
+    // E.g. checking a league squad against UEFA registration limits
+    pub fn validate_for(&self, rules: &SquadRules) -> Vec<SquadError> {
+        rules.validate(&self.players)
+    }
+    
+    // Returns false if nobody wears `jersey_number`
+    pub fn set_rating(&mut self, jersey_number: u8, rating: u8) -> Result<bool, SquadError> {
+        if rating > MAX_RATING {
+            let player = self.get_player_by_number(jersey_number).map(|p| p.name.clone()).unwrap_or_default();
+            return Err(SquadError::InvalidRating { player, rating });
+        }
+        match self.players.iter_mut().find(|p| p.jersey_number == jersey_number) {
+            Some(player) => {
+                player.rating = rating;
+                Ok(true)
+            }
+            None => Ok(false),
+        }
     }
     
     pub fn remove_player(&mut self, jersey_number: u8) -> Option<Player> {
@@ -60,4 +144,26 @@ impl Team {
     pub fn list_squad(&self) -> Vec<String> {
         self.players.iter().map(|p| p.display_info()).collect()
     }
+    
+    // Highest-rated player for every slot of a formation like "4-3-3"
+    pub fn best_xi(&self, formation: &str) -> Result<StartingXi<'_>, FormationError> {
+        Ok(StartingXi::pick(&self.players, Formation::parse(formation)?))
+    }
+    
+    // Strongest lineup among the candidate formations; complete lineups beat
+    // ones with gaps, then the combined rating decides
+    pub fn best_formation(&self, formations: &[&str]) -> Result<Option<StartingXi<'_>>, FormationError> {
+        let mut best: Option<StartingXi> = None;
+        for formation in formations {
+            let xi = self.best_xi(formation)?;
+            let better = match &best {
+                Some(b) => (xi.is_complete(), xi.total_rating()) > (b.is_complete(), b.total_rating()),
+                None => true,
+            };
+            if better {
+                best = Some(xi);
+            }
+        }
+        Ok(best)
+    }
 }
\ No newline at end of file
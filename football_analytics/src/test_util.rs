@@ -0,0 +1,22 @@
+use crate::date::MatchDate;
+use crate::match_data::MatchData;
+use crate::player::{Player, Position};
+
+// Fixture factories shared by the unit tests
+
+pub(crate) fn date(year: u16, month: u8, day: u8) -> MatchDate {
+    MatchDate::new(year, month, day).unwrap()
+}
+
+// A result without scorers, played on 1 January 2024
+pub(crate) fn result(home: &str, away: &str, home_score: u8, away_score: u8) -> MatchData {
+    result_on(date(2024, 1, 1), home, away, home_score, away_score)
+}
+
+pub(crate) fn result_on(date: MatchDate, home: &str, away: &str, home_score: u8, away_score: u8) -> MatchData {
+    MatchData::new(date, home.to_string(), away.to_string(), home_score, away_score, vec![], vec![])
+}
+
+pub(crate) fn player(name: &str, jersey_number: u8, position: Position, rating: u8) -> Player {
+    Player::new(name.to_string(), jersey_number, position, rating)
+}
@@ -0,0 +1,423 @@
+use std::fmt;
+
+use crate::date::{DateRange, MatchDate};
+use crate::player::Player;
+use crate::registry::PlayerId;
+use crate::squad::SquadError;
+use crate::team::Team;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Contract {
+    pub until: MatchDate,
+    pub weekly_wage: Option<u32>,
+}
+
+// What the buying club agreed to pay
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct TransferTerms {
+    pub fee: Option<u64>,           // None for free transfers
+    pub contract: Option<Contract>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Transfer {
+    pub date: MatchDate,
+    pub player: Player,
+    pub player_id: PlayerId,
+    pub from_team: Option<String>,  // None when signing a free agent
+    pub to_team: Option<String>,    // None when a player is released
+    pub fee: Option<u64>,           // None for free transfers
+    pub contract: Option<Contract>,
+}
+
+// A stretch of time a player spent at one club; `left` is the day they
+// moved on, so a player joins and leaves on the same date of a transfer
+#[derive(Debug, Clone, PartialEq)]
+pub struct Spell {
+    pub team: String,
+    pub player: Player,
+    pub player_id: PlayerId,
+    pub joined: MatchDate,
+    pub left: Option<MatchDate>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum TransferError {
+    PlayerNotFound { team: String, jersey_number: u8 },
+    SameTeam,
+    BeforeLastMove(MatchDate),      // The player already moved after this date
+    Squad(SquadError),              // The buying club can't register the player
+}
+
+// Every move between squads plus the spells they produce. Players are
+// told apart by an id the history hands out when it first sees them, so
+// two players sharing a name never get mixed up.
+#[derive(Debug, Clone, Default)]
+pub struct SquadHistory {
+    transfers: Vec<Transfer>,
+    spells: Vec<Spell>,
+    next_id: u32,
+}
+
+impl TransferTerms {
+    pub fn free() -> Self {
+        TransferTerms::default()
+    }
+
+    pub fn fee(fee: u64) -> Self {
+        TransferTerms {
+            fee: Some(fee),
+            contract: None,
+        }
+    }
+
+    pub fn with_contract(mut self, contract: Contract) -> Self {
+        self.contract = Some(contract);
+        self
+    }
+}
+
+impl Spell {
+    pub fn is_active_on(&self, date: MatchDate) -> bool {
+        self.joined <= date && self.left.is_none_or(|left| date < left)
+    }
+
+    pub fn overlaps(&self, range: &DateRange) -> bool {
+        let starts_in_time = range.to.is_none_or(|to| self.joined <= to);
+        let ends_in_time = match (self.left, range.from) {
+            (Some(left), Some(from)) => left > from,
+            _ => true,
+        };
+        starts_in_time && ends_in_time
+    }
+}
+
+impl SquadHistory {
+    pub fn new() -> Self {
+        SquadHistory {
+            transfers: Vec::new(),
+            spells: Vec::new(),
+            next_id: 0,
+        }
+    }
+
+    // Records the current squad as having been together since `since`.
+    // Players already recorded at the club keep their existing spell, so
+    // recording the same squad twice changes nothing.
+    pub fn record_squad(&mut self, team: &Team, since: MatchDate) {
+        for player in team.players() {
+            let recorded = self
+                .current_spell(&team.name, player.jersey_number)
+                .is_some_and(|s| s.player.name == player.name);
+            if !recorded {
+                let id = self.new_id();
+                self.open_spell(&team.name, player, id, since);
+            }
+        }
+    }
+
+    // The id of whoever wears `jersey_number` at `team`, if the history
+    // knows about them
+    pub fn player_id(&self, team: &str, jersey_number: u8) -> Option<PlayerId> {
+        self.current_spell(team, jersey_number).map(|s| s.player_id)
+    }
+
+    // Moves the player wearing `jersey_number` at `from` to `to`, where they
+    // take `new_jersey_number`
+    pub fn transfer(
+        &mut self,
+        from: &mut Team,
+        to: &mut Team,
+        jersey_number: u8,
+        new_jersey_number: u8,
+        date: MatchDate,
+        terms: TransferTerms,
+    ) -> Result<&Transfer, TransferError> {
+        if from.name == to.name {
+            return Err(TransferError::SameTeam);
+        }
+        let (mut incoming, _) = self.find_player(from, jersey_number, date)?;
+        incoming.jersey_number = new_jersey_number;
+        // Checked up front so a failed move leaves both squads untouched
+        to.can_add_player(&incoming)?;
+
+        let (_, player_id) = self.take_player(from, jersey_number, date)?;
+        to.add_player(incoming.clone())?;
+        self.open_spell(&to.name, &incoming, player_id, date);
+
+        self.transfers.push(Transfer {
+            date,
+            player: incoming,
+            player_id,
+            from_team: Some(from.name.clone()),
+            to_team: Some(to.name.clone()),
+            fee: terms.fee,
+            contract: terms.contract,
+        });
+        Ok(self.transfers.last().expect("just pushed"))
+    }
+
+    // Free agents get a new id, even if they were released from a club
+    // the history knows about
+    pub fn sign(
+        &mut self,
+        to: &mut Team,
+        player: Player,
+        date: MatchDate,
+        terms: TransferTerms,
+    ) -> Result<&Transfer, TransferError> {
+        to.add_player(player.clone())?;
+        let player_id = self.new_id();
+        self.open_spell(&to.name, &player, player_id, date);
+
+        self.transfers.push(Transfer {
+            date,
+            player,
+            player_id,
+            from_team: None,
+            to_team: Some(to.name.clone()),
+            fee: terms.fee,
+            contract: terms.contract,
+        });
+        Ok(self.transfers.last().expect("just pushed"))
+    }
+
+    pub fn release(&mut self, from: &mut Team, jersey_number: u8, date: MatchDate) -> Result<Player, TransferError> {
+        let (player, player_id) = self.take_player(from, jersey_number, date)?;
+        self.transfers.push(Transfer {
+            date,
+            player: player.clone(),
+            player_id,
+            from_team: Some(from.name.clone()),
+            to_team: None,
+            fee: None,
+            contract: None,
+        });
+        Ok(player)
+    }
+
+    // Oldest first
+    pub fn transfers(&self) -> &[Transfer] {
+        &self.transfers
+    }
+
+    pub fn transfers_for(&self, team: &str) -> Vec<&Transfer> {
+        self.transfers
+            .iter()
+            .filter(|t| t.from_team.as_deref() == Some(team) || t.to_team.as_deref() == Some(team))
+            .collect()
+    }
+
+    // Every club a player has been at, oldest first
+    pub fn career(&self, player: PlayerId) -> Vec<&Spell> {
+        let mut spells: Vec<&Spell> = self.spells.iter().filter(|s| s.player_id == player).collect();
+        spells.sort_by_key(|s| s.joined);
+        spells
+    }
+
+    // Everyone who was at `team` at some point during `range`, e.g.
+    // `Season::range()`
+    pub fn players_during(&self, team: &str, range: &DateRange) -> Vec<&Spell> {
+        self.spells
+            .iter()
+            .filter(|s| s.team == team && s.overlaps(range))
+            .collect()
+    }
+
+    pub fn squad_on(&self, team: &str, date: MatchDate) -> Vec<&Player> {
+        self.spells
+            .iter()
+            .filter(|s| s.team == team && s.is_active_on(date))
+            .map(|s| &s.player)
+            .collect()
+    }
+
+    // A copy of `team` holding the squad it had on `date`
+    pub fn team_as_of(&self, team: &Team, date: MatchDate) -> Team {
+        // The squad was valid when it was recorded, so skip today's checks
+        Team::new(team.name.clone(), team.city.clone())
+            .with_rules(team.rules.clone())
+            .with_players_unchecked(self.squad_on(&team.name, date).into_iter().cloned().collect())
+    }
+
+    // The player wearing `jersey_number` and their id, if they can move on
+    // `date`. The id is None for squads that were never recorded.
+    fn find_player(
+        &self,
+        team: &Team,
+        jersey_number: u8,
+        date: MatchDate,
+    ) -> Result<(Player, Option<PlayerId>), TransferError> {
+        let player = team
+            .get_player_by_number(jersey_number)
+            .cloned()
+            .ok_or_else(|| TransferError::PlayerNotFound {
+                team: team.name.clone(),
+                jersey_number,
+            })?;
+        let id = self.player_id(&team.name, jersey_number);
+        if let Some(id) = id {
+            self.check_order(id, date)?;
+        }
+        Ok((player, id))
+    }
+
+    fn take_player(
+        &mut self,
+        from: &mut Team,
+        jersey_number: u8,
+        date: MatchDate,
+    ) -> Result<(Player, PlayerId), TransferError> {
+        let (_, id) = self.find_player(from, jersey_number, date)?;
+        let player = from.remove_player(jersey_number).expect("player was just found");
+        let id = match id {
+            Some(id) => {
+                let spell = self
+                    .spells
+                    .iter_mut()
+                    .find(|s| s.player_id == id && s.left.is_none())
+                    .expect("ids come from open spells");
+                spell.left = Some(date);
+                id
+            }
+            None => self.new_id(),
+        };
+        Ok((player, id))
+    }
+
+    fn current_spell(&self, team: &str, jersey_number: u8) -> Option<&Spell> {
+        self.spells
+            .iter()
+            .find(|s| s.team == team && s.player.jersey_number == jersey_number && s.left.is_none())
+    }
+
+    fn check_order(&self, player: PlayerId, date: MatchDate) -> Result<(), TransferError> {
+        let last_move = self
+            .spells
+            .iter()
+            .filter(|s| s.player_id == player)
+            .flat_map(|s| [Some(s.joined), s.left])
+            .flatten()
+            .max();
+        match last_move {
+            Some(last) if date < last => Err(TransferError::BeforeLastMove(last)),
+            _ => Ok(()),
+        }
+    }
+
+    fn new_id(&mut self) -> PlayerId {
+        self.next_id += 1;
+        PlayerId(self.next_id)
+    }
+
+    fn open_spell(&mut self, team: &str, player: &Player, player_id: PlayerId, date: MatchDate) {
+        self.spells.push(Spell {
+            team: team.to_string(),
+            player: player.clone(),
+            player_id,
+            joined: date,
+            left: None,
+        });
+    }
+}
+
+impl fmt::Display for TransferError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TransferError::PlayerNotFound { team, jersey_number } => {
+                write!(f, "{} has no player wearing #{}", team, jersey_number)
+            }
+            TransferError::SameTeam => write!(f, "a player can't transfer to the club they're already at"),
+            TransferError::BeforeLastMove(date) => write!(f, "the player already moved on {}", date),
+            TransferError::Squad(e) => write!(f, "{}", e),
+        }
+    }
+}
+
+impl std::error::Error for TransferError {}
+
+impl From<SquadError> for TransferError {
+    fn from(e: SquadError) -> Self {
+        TransferError::Squad(e)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::player::Position;
+    use crate::squad::SquadRules;
+    use crate::test_util::{date, player};
+
+    #[test]
+    fn test_transfer_keeps_history() {
+        let mut barcelona = Team::new("Barcelona".to_string(), "Barcelona".to_string());
+        let mut psg = Team::new("PSG".to_string(), "Paris".to_string());
+        barcelona.add_player(player("Neymar", 11, Position::Forward, 90)).unwrap();
+
+        let mut history = SquadHistory::new();
+        history.record_squad(&barcelona, date(2013, 7, 1));
+        let transfer = history
+            .transfer(&mut barcelona, &mut psg, 11, 10, date(2017, 8, 3), TransferTerms::fee(222_000_000))
+            .unwrap();
+        assert_eq!(transfer.player.jersey_number, 10);
+        let neymar = transfer.player_id;
+
+        assert_eq!(barcelona.squad_size(), 0);
+        assert_eq!(psg.get_player_by_number(10).unwrap().name, "Neymar");
+        assert_eq!(history.team_as_of(&barcelona, date(2016, 1, 1)).squad_size(), 1);
+        assert_eq!(history.squad_on("Barcelona", date(2017, 8, 3)).len(), 0);
+        assert_eq!(history.players_during("Barcelona", &DateRange::season(2017).unwrap()).len(), 1);
+        assert_eq!(history.players_during("Barcelona", &DateRange::season(2018).unwrap()).len(), 0);
+        assert_eq!(history.career(neymar).len(), 2);
+
+        let early = history.release(&mut psg, 10, date(2017, 1, 1));
+        assert_eq!(early, Err(TransferError::BeforeLastMove(date(2017, 8, 3))));
+        assert!(matches!(
+            history.release(&mut psg, 7, date(2023, 8, 15)),
+            Err(TransferError::PlayerNotFound { .. })
+        ));
+    }
+
+    #[test]
+    fn test_players_sharing_a_name_are_kept_apart() {
+        let mut real_madrid = Team::new("Real Madrid".to_string(), "Madrid".to_string());
+        let mut sevilla = Team::new("Sevilla".to_string(), "Seville".to_string());
+        let mut psg = Team::new("PSG".to_string(), "Paris".to_string());
+        real_madrid.add_player(player("Sergio", 4, Position::Defender, 88)).unwrap();
+        sevilla.add_player(player("Sergio", 16, Position::Midfielder, 80)).unwrap();
+
+        let mut history = SquadHistory::new();
+        history.record_squad(&real_madrid, date(2005, 7, 1));
+        history.record_squad(&sevilla, date(2005, 7, 1));
+        // Recording the same squad again opens no new spells
+        history.record_squad(&real_madrid, date(2010, 7, 1));
+        assert_eq!(history.squad_on("Real Madrid", date(2015, 1, 1)).len(), 1);
+
+        let ramos = history.player_id("Real Madrid", 4).unwrap();
+        let other = history.player_id("Sevilla", 16).unwrap();
+        assert_ne!(ramos, other);
+        history
+            .transfer(&mut real_madrid, &mut psg, 4, 4, date(2021, 7, 8), TransferTerms::free())
+            .unwrap();
+
+        // One Sergio moving doesn't hold the other back or end their spell
+        assert!(history.release(&mut sevilla, 16, date(2019, 6, 30)).is_ok());
+        assert_eq!(history.career(ramos).len(), 2);
+        assert_eq!(history.career(other).len(), 1);
+        assert_eq!(history.career(other)[0].left, Some(date(2019, 6, 30)));
+    }
+
+    #[test]
+    fn test_past_squads_keep_their_rules() {
+        let mut ajax = Team::new("Ajax".to_string(), "Amsterdam".to_string()).with_rules(SquadRules::uefa());
+        ajax.add_player(player("Edwin van der Sar", 1, Position::Goalkeeper, 88)).unwrap();
+
+        let mut history = SquadHistory::new();
+        history.record_squad(&ajax, date(1995, 7, 1));
+        let past = history.team_as_of(&ajax, date(1996, 1, 1));
+
+        assert_eq!(past.rules, SquadRules::uefa());
+        assert_eq!(past.validate(), vec![SquadError::TooFewGoalkeepers { required: 2, found: 1 }]);
+    }
+}
@@ -0,0 +1,238 @@
+use std::collections::HashMap;
+
+use crate::events::{BodyPart, Shot, ShotSituation};
+use crate::match_data::MatchData;
+
+// Logistic expected goals model: a shot's chance of scoring comes from its
+// distance and angle to goal plus adjustments for headers and the kind of
+// situation. Penalties get a fixed value, kept within 0 to 1.
+#[derive(Debug, Clone, PartialEq)]
+pub struct XgModel {
+    pub intercept: f64,
+    pub distance_coef: f64,     // Per metre
+    pub angle_coef: f64,        // Per radian
+    pub header_coef: f64,
+    pub counter_coef: f64,
+    pub set_piece_coef: f64,
+    pub free_kick_coef: f64,
+    pub penalty_xg: f64,
+}
+
+impl Default for XgModel {
+    fn default() -> Self {
+        XgModel {
+            intercept: -1.1,
+            distance_coef: -0.09,
+            angle_coef: 1.6,
+            header_coef: -0.8,
+            counter_coef: 0.3,
+            set_piece_coef: -0.2,
+            free_kick_coef: -0.3,
+            penalty_xg: 0.76,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct TeamXg {
+    pub shots: usize,
+    pub xg: f64,
+    pub goals: usize,
+    pub shots_against: usize,
+    pub xga: f64,
+    pub goals_against: usize,
+}
+
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct PlayerXg {
+    pub shots: usize,
+    pub xg: f64,
+    pub goals: usize,           // Shots that went in; own goals never count
+}
+
+impl XgModel {
+    pub fn shot_xg(&self, shot: &Shot) -> f64 {
+        if shot.situation == ShotSituation::Penalty {
+            return self.penalty_xg.clamp(0.0, 1.0);
+        }
+
+        let mut logit = self.intercept
+            + self.distance_coef * shot.distance_to_goal()
+            + self.angle_coef * shot.goal_angle();
+
+        if shot.body_part == BodyPart::Head {
+            logit += self.header_coef;
+        }
+        logit += match shot.situation {
+            ShotSituation::Counter => self.counter_coef,
+            ShotSituation::SetPiece => self.set_piece_coef,
+            ShotSituation::DirectFreeKick => self.free_kick_coef,
+            ShotSituation::OpenPlay | ShotSituation::Penalty => 0.0,
+        };
+
+        1.0 / (1.0 + (-logit).exp())
+    }
+
+    pub fn total_xg(&self, shots: &[Shot]) -> f64 {
+        shots.iter().map(|s| self.shot_xg(s)).sum()
+    }
+
+    // xG for and against per team. Goals are the final scores, so own goals
+    // show up in `goals` without any xG behind them.
+    pub fn team_totals(&self, matches: &[MatchData]) -> HashMap<String, TeamXg> {
+        let mut totals: HashMap<String, TeamXg> = HashMap::new();
+
+        for m in matches {
+            let home_xg = self.total_xg(&m.home_shots);
+            let away_xg = self.total_xg(&m.away_shots);
+
+            let home = totals.entry(m.home_team.clone()).or_default();
+            home.shots += m.home_shots.len();
+            home.xg += home_xg;
+            home.goals += m.home_score as usize;
+            home.shots_against += m.away_shots.len();
+            home.xga += away_xg;
+            home.goals_against += m.away_score as usize;
+
+            let away = totals.entry(m.away_team.clone()).or_default();
+            away.shots += m.away_shots.len();
+            away.xg += away_xg;
+            away.goals += m.away_score as usize;
+            away.shots_against += m.home_shots.len();
+            away.xga += home_xg;
+            away.goals_against += m.home_score as usize;
+        }
+
+        totals
+    }
+
+    pub fn player_totals(&self, matches: &[MatchData]) -> HashMap<String, PlayerXg> {
+        let mut totals: HashMap<String, PlayerXg> = HashMap::new();
+
+        for m in matches {
+            for shot in m.home_shots.iter().chain(m.away_shots.iter()) {
+                let player = totals.entry(shot.player.clone()).or_default();
+                player.shots += 1;
+                player.xg += self.shot_xg(shot);
+                if shot.is_goal() {
+                    player.goals += 1;
+                }
+            }
+        }
+
+        totals
+    }
+}
+
+impl TeamXg {
+    // Positive when a team scores more than its chances suggest
+    pub fn finishing_difference(&self) -> f64 {
+        self.goals as f64 - self.xg
+    }
+
+    pub fn xg_difference(&self) -> f64 {
+        self.xg - self.xga
+    }
+}
+
+impl PlayerXg {
+    pub fn finishing_difference(&self) -> f64 {
+        self.goals as f64 - self.xg
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::events::ShotOutcome;
+    use crate::test_util::result;
+
+    fn shot(player: &str, at: (f64, f64), situation: ShotSituation, outcome: ShotOutcome) -> Shot {
+        Shot::new(player.to_string(), 30, at, BodyPart::RightFoot, situation, outcome)
+    }
+
+    fn open_play(at: (f64, f64)) -> Shot {
+        shot("Shooter", at, ShotSituation::OpenPlay, ShotOutcome::Saved)
+    }
+
+    #[test]
+    fn test_shot_xg_falls_with_distance_and_angle() {
+        let model = XgModel::default();
+        let xgs: Vec<f64> = [100.0, 90.0, 75.0, 55.0, 30.0, 0.0]
+            .iter()
+            .map(|&x| model.shot_xg(&open_play((x, 34.0))))
+            .collect();
+        assert!(xgs.windows(2).all(|w| w[0] > w[1]), "{:?}", xgs);
+
+        // Same distance to the goal centre, moving out towards the touchline
+        let distance = 12.0_f64;
+        let xgs: Vec<f64> = [0.0_f64, 0.4, 0.8, 1.2]
+            .iter()
+            .map(|&a| model.shot_xg(&open_play((105.0 - distance * a.cos(), 34.0 + distance * a.sin()))))
+            .collect();
+        assert!(xgs.windows(2).all(|w| w[0] > w[1]), "{:?}", xgs);
+
+        let extreme = XgModel {
+            intercept: 50.0,
+            ..XgModel::default()
+        };
+        for model in [XgModel::default(), extreme] {
+            for at in [(105.0, 34.0), (104.9, 34.0), (0.0, 0.0), (60.0, 68.0)] {
+                let xg = model.shot_xg(&open_play(at));
+                assert!((0.0..=1.0).contains(&xg), "{} at {:?}", xg, at);
+            }
+        }
+    }
+
+    #[test]
+    fn test_penalties_get_the_fixed_value() {
+        let model = XgModel::default();
+        let penalty = shot("Taker", (94.0, 34.0), ShotSituation::Penalty, ShotOutcome::Goal);
+        assert_eq!(model.shot_xg(&penalty), 0.76);
+
+        let generous = XgModel {
+            penalty_xg: 1.5,
+            ..XgModel::default()
+        };
+        assert_eq!(generous.shot_xg(&penalty), 1.0);
+    }
+
+    #[test]
+    fn test_totals_over_several_matches() {
+        let model = XgModel::default();
+        let tap_in = || shot("Kane", (103.0, 34.0), ShotSituation::OpenPlay, ShotOutcome::Goal);
+        let long_shot = || shot("Kane", (75.0, 30.0), ShotSituation::OpenPlay, ShotOutcome::OffTarget);
+        let header = || shot("Rudiger", (99.0, 36.0), ShotSituation::SetPiece, ShotOutcome::Saved);
+
+        let mut first = result("Bayern", "Real", 1, 0);
+        first.home_shots = vec![tap_in(), long_shot()];
+        first.away_shots = vec![header()];
+        // The away goal is an own goal, so it has no shot behind it
+        let mut second = result("Real", "Bayern", 1, 1);
+        second.home_shots = vec![header()];
+        second.away_shots = vec![tap_in()];
+
+        let (tap_in_xg, long_shot_xg, header_xg) =
+            (model.shot_xg(&tap_in()), model.shot_xg(&long_shot()), model.shot_xg(&header()));
+        let matches = [first, second];
+        let teams = model.team_totals(&matches);
+
+        let bayern = &teams["Bayern"];
+        assert_eq!((bayern.shots, bayern.goals, bayern.shots_against, bayern.goals_against), (3, 2, 2, 1));
+        assert!((bayern.xg - (2.0 * tap_in_xg + long_shot_xg)).abs() < 1e-12);
+        assert!((bayern.xga - 2.0 * header_xg).abs() < 1e-12);
+        assert!((bayern.finishing_difference() - (2.0 - bayern.xg)).abs() < 1e-12);
+        assert!((bayern.xg_difference() + teams["Real"].xg_difference()).abs() < 1e-12);
+
+        let real = &teams["Real"];
+        assert_eq!((real.goals, real.goals_against), (1, 2));
+        assert!((real.xg - bayern.xga).abs() < 1e-12);
+
+        let players = model.player_totals(&matches);
+        let kane = &players["Kane"];
+        assert_eq!((kane.shots, kane.goals), (3, 2));
+        assert!((kane.xg - bayern.xg).abs() < 1e-12);
+        assert_eq!((players["Rudiger"].shots, players["Rudiger"].goals), (2, 0));
+        assert!((players["Rudiger"].finishing_difference() + 2.0 * header_xg).abs() < 1e-12);
+    }
+}